@@ -45,7 +45,7 @@ class RegionType(Enum):
             return '自治州'
         elif self == RegionType.tu_zz_county:
             return '土家族自治县'
-        elif self == RegionType.man_zz_county:
+        elif self == RegionType.miao_zz_county:
             return '苗族自治县'
         elif self == RegionType.menggu_zz_county:
             return '蒙古族自治县'
@@ -94,9 +94,17 @@ class RegionCtr:
     def __init__(self, file_name: str = 'region.dat') -> None:
         self.file_name = file_name
 
-    def pack(self, data_list: list[tuple[str, str, str]] | list[tuple[str, str]], version: int = 0) -> bool:
+    def pack(
+        self,
+        data_list: list[tuple[str, str, str]] | list[tuple[str, str]],
+        version: int = 0,
+        sort_chars: bool = False,
+    ) -> bool:
         version = version or int(datetime.now().strftime('%Y%m%d%H'))
         print('version: ', version)
+        # 字符集按gbk编码排序时，版本号最高位置1，相同的数据总是生成相同的文件
+        if sort_chars:
+            version |= 1 << 31
         with open(self.file_name, 'wb') as f:
             # 写32位版本号
             f.write(version.to_bytes(length=4))
@@ -111,6 +119,8 @@ class RegionCtr:
                 for c in name:
                     chars.add(c)
             char_list: list[str] = list(chars)
+            if sort_chars:
+                char_list.sort(key=lambda c: c.encode('gbk'))
             # 字符映射
             char_map: dict[str, int] = {}
             for i, c in enumerate(char_list):
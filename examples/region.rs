@@ -4,7 +4,7 @@ use region_cn::region::Region;
 use serde_json::Value;
 
 pub fn main() {
-    let mut region = Region::new(PathBuf::from("data/region.dat"));
+    let region = Region::new(PathBuf::from("data/region.dat"));
     // 直接在region.dat中搜索
     match region.search_with_data("530925") {
         Ok(data) => {
@@ -24,7 +24,7 @@ pub fn main() {
     let mut origin_file = File::open(PathBuf::from("data/region_full.txt")).unwrap();
     let mut file_string = String::new();
     origin_file.read_to_string(&mut file_string).unwrap();
-    let mut searcher = Region::new(PathBuf::from("data/region_full.dat"));
+    let searcher = Region::new(PathBuf::from("data/region_full.dat"));
     let json_data: Value = serde_json::from_str(&file_string).unwrap();
     for x in json_data.as_array().unwrap() {
         let code = x.get(0).unwrap().as_str().unwrap();
@@ -51,7 +51,7 @@ pub fn main() {
     let mut origin_file = File::open(PathBuf::from("data/region.txt")).unwrap();
     let mut file_string = String::new();
     origin_file.read_to_string(&mut file_string).unwrap();
-    let mut searcher = Region::new(PathBuf::from("data/region.dat"));
+    let searcher = Region::new(PathBuf::from("data/region.dat"));
     let json_data: Value = serde_json::from_str(&file_string).unwrap();
     for x in json_data.as_array().unwrap() {
         let code = x.get(0).unwrap().as_str().unwrap();
@@ -62,5 +62,8 @@ pub fn main() {
         let result = searcher.search_with_data(code).unwrap();
         println!("{code} {region_name} => {}", result.name);
         assert!(result.name.ends_with(&region_name));
+        // 通过名称反查
+        let result = searcher.search_by_name(&result.name).unwrap();
+        assert!(result.iter().any(|x| x.region_code == code));
     }
 }
@@ -0,0 +1,31 @@
+//! 任意数据传给`Region::verify_bytes`和`Region::from_bytes`，所有解析和查询的方法都只能返回错误，不能panic
+//!
+//! cargo +nightly fuzz run from_bytes
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use region_cn::{complete::CompleteOptions, region::Region};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Region::verify_bytes(data);
+    let Ok(region) = Region::from_bytes(data.to_vec()) else {
+        return;
+    };
+    let _ = region.verify();
+    let _ = region.get_record_from_data();
+    for code in ["110000", "110101", "530925", "429004", "999999"] {
+        let _ = region.search_with_data(code);
+        let _ = region.search_with_trie(code);
+        let _ = region.children(code);
+        let _ = region.siblings(code);
+        let _ = region.search_as_of(code, 2000);
+        let _ = region.successors(code);
+        let _ = region.suggest_successors(code);
+    }
+    let _ = region.iter_valid_in(2000).map(|x| x.count());
+    let _ = region.provinces();
+    let _ = region.search_by_name("临沧市双江县");
+    let _ = region.search_fuzzy("shuangjiang", 5);
+    let _ = region.complete("临", &CompleteOptions::new(5));
+    let _ = region.parse_address("云南临沧双江县勐勐镇");
+});
@@ -0,0 +1,219 @@
+//! 从地址文本中解析地区，比如`云南临沧双江县勐勐镇`解析出`530925`和剩下的`勐勐镇`
+use std::{ops::Range, sync::Arc};
+
+use crate::{name_index::NameIndex, AdminLevel, RegionItem};
+
+// 地址开头可以省略的国家名称
+const COUNTRY_PREFIXES: [&str; 2] = ["中华人民共和国", "中国"];
+
+/// 地址的解析结果
+#[derive(Debug, Clone)]
+pub struct ParsedAddress {
+    /// 匹配到的最后一级地区，包含每一级的名称
+    pub region: RegionItem,
+    /// 匹配到的地区名称在输入中的字节范围
+    pub matched: Range<usize>,
+    /// 地区名称之后剩下的文本，比如街道和门牌号
+    pub rest: String,
+    /// 置信度，0到1之间，匹配到的级别越低、使用全称越多越高，有多个相同的匹配时降低
+    pub confidence: f32,
+}
+
+/// 地址解析器，使用`NameIndex`的别名匹配，可以和其他功能共用同一个索引
+#[derive(Debug, Clone, Default)]
+pub struct AddressParser {
+    index: Arc<NameIndex>,
+    // 最长的别名的字符数
+    max_alias_chars: usize,
+}
+
+// 匹配到的一级地区
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    idx: usize,
+    start: usize,
+    end: usize,
+    // 是否匹配的全称
+    full: bool,
+}
+
+impl AddressParser {
+    /// 通过名称索引构建，`Region::parse_address`使用的是`Region`中缓存的索引
+    pub fn new(index: Arc<NameIndex>) -> Self {
+        let max_alias_chars = index
+            .alias_map
+            .keys()
+            .map(|x| x.chars().count())
+            .max()
+            .unwrap_or_default();
+        Self {
+            index,
+            max_alias_chars,
+        }
+    }
+
+    /// 从地址开头依次匹配省、市、县的全称或者简称，每一级都可以省略，
+    /// 比如`北京市东城区`、`云南临沧双江县`和`湖北仙桃`。没有匹配到任何地区时返回`None`
+    pub fn parse(&self, address: &str) -> Option<ParsedAddress> {
+        let mut start = skip_whitespace(address, 0);
+        for prefix in COUNTRY_PREFIXES {
+            if address[start..].starts_with(prefix) {
+                start = skip_whitespace(address, start + prefix.len());
+                break;
+            }
+        }
+        let mut chains = Vec::new();
+        self.walk(address, start, None, &mut Vec::new(), &mut chains);
+        // 匹配的文本越长、级别越多越好，然后是未废止的和使用全称的
+        let score = |chain: &Vec<Step>| {
+            let last = chain[chain.len() - 1];
+            (
+                last.end,
+                chain.len(),
+                self.index.items[last.idx].discard_year == 0,
+                chain.iter().filter(|x| x.full).count(),
+            )
+        };
+        let best_score = chains.iter().map(score).max()?;
+        let mut best: Vec<&Vec<Step>> = chains.iter().filter(|x| score(x) == best_score).collect();
+        best.sort_by_key(|x| x[x.len() - 1].idx);
+        best.dedup_by_key(|x| x[x.len() - 1].idx);
+        let chain = best[0];
+        let last = chain[chain.len() - 1];
+        let region = self.index.items[last.idx].clone();
+        let level_factor = match AdminLevel::of(&region.region_code) {
+            AdminLevel::County => 1.0,
+            AdminLevel::Prefecture => 0.8,
+            AdminLevel::Province => 0.5,
+        };
+        // 简称越短越容易误匹配
+        let quality = chain
+            .iter()
+            .map(
+                |x| match (x.full, address[x.start..x.end].chars().count()) {
+                    (true, _) => 1.0,
+                    (false, n) if n >= 3 => 0.9,
+                    _ => 0.8,
+                },
+            )
+            .sum::<f32>()
+            / chain.len() as f32;
+        let discard_factor = if region.discard_year == 0 { 1.0 } else { 0.9 };
+        Some(ParsedAddress {
+            matched: chain[0].start..last.end,
+            rest: address[last.end..].trim().to_string(),
+            confidence: level_factor * quality * discard_factor / best.len() as f32,
+            region,
+        })
+    }
+
+    /// 从`pos`开始匹配`parent`的下级，匹配不到时记录当前的结果
+    fn walk(
+        &self,
+        address: &str,
+        pos: usize,
+        parent: Option<usize>,
+        chain: &mut Vec<Step>,
+        chains: &mut Vec<Vec<Step>>,
+    ) {
+        let mut matched = false;
+        for (end, _) in address[pos..]
+            .char_indices()
+            .skip(1)
+            .map(|(i, c)| (pos + i, c))
+            .chain([(address.len(), ' ')])
+            .take(self.max_alias_chars)
+        {
+            let Some(candidates) = self.index.alias_map.get(&address[pos..end]) else {
+                continue;
+            };
+            for idx in candidates {
+                if parent.is_some_and(|p| !self.index.ancestors[*idx].contains(&p)) {
+                    continue;
+                }
+                matched = true;
+                chain.push(Step {
+                    idx: *idx,
+                    start: pos,
+                    end,
+                    full: self.index.aliases[*idx][0] == address[pos..end],
+                });
+                self.walk(
+                    address,
+                    skip_whitespace(address, end),
+                    Some(*idx),
+                    chain,
+                    chains,
+                );
+                chain.pop();
+            }
+        }
+        if !matched && !chain.is_empty() {
+            chains.push(chain.clone());
+        }
+    }
+}
+
+/// 跳过`pos`开始的空白字符
+fn skip_whitespace(address: &str, pos: usize) -> usize {
+    address[pos..]
+        .char_indices()
+        .find(|(_, c)| !c.is_whitespace())
+        .map_or(address.len(), |(i, _)| pos + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::region::Region;
+
+    #[test]
+    fn test_parse_address() {
+        let region = Region::open("data/region_full.dat").unwrap();
+        let address = "云南临沧双江县勐勐镇";
+        let result = region.parse_address(address).unwrap();
+        assert_eq!(result.region.region_code, "530925");
+        assert_eq!(&address[result.matched.clone()], "云南临沧双江县");
+        assert_eq!(result.rest, "勐勐镇");
+        let result = region.parse_address("中国 北京市东城区xx路1号").unwrap();
+        assert_eq!(result.region.name, "北京市东城区");
+        assert_eq!(result.rest, "xx路1号");
+        assert_eq!(result.confidence, 1.0);
+        assert_eq!(
+            region
+                .parse_address("吉林省长春市朝阳区")
+                .unwrap()
+                .region
+                .region_code,
+            "220104"
+        );
+        assert_eq!(
+            region
+                .parse_address("湖北仙桃")
+                .unwrap()
+                .region
+                .region_slice,
+            vec!["湖北省", "仙桃市"]
+        );
+        // 新区、矿区等由多个字组成的后缀可以省略
+        let result = region.parse_address("上海浦东张江路").unwrap();
+        assert_eq!(result.region.region_code, "310115");
+        assert_eq!(result.rest, "张江路");
+        assert_eq!(
+            region.parse_address("神农架").unwrap().region.region_code,
+            "429021"
+        );
+        assert_eq!(
+            region
+                .parse_address("太原万柏林区")
+                .unwrap()
+                .region
+                .region_code,
+            "140109"
+        );
+        // 没有上级时北京和长春都有朝阳区，返回地区码小的
+        let result = region.parse_address("朝阳区").unwrap();
+        assert_eq!(result.region.region_code, "110105");
+        assert_eq!(result.confidence, 0.5);
+        assert!(region.parse_address("xx路1号").is_err());
+    }
+}
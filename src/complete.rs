@@ -0,0 +1,190 @@
+//! 按名称前缀补全地区，用于输入时的提示，比如输入`临`提示`临沧市`
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{name_index::NameIndex, AdminLevel, RegionItem};
+
+/// 补全的选项，通过`new`指定返回的数量，默认不包含废止的地区
+#[derive(Debug, Clone)]
+pub struct CompleteOptions {
+    limit: usize,
+    parent: Option<String>,
+    level: Option<AdminLevel>,
+    include_discarded: bool,
+}
+
+impl CompleteOptions {
+    /// 最多返回`limit`个结果
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            parent: None,
+            level: None,
+            include_discarded: false,
+        }
+    }
+
+    /// 只返回`parent`的下级，包括下级的下级，比如`530000`下的`530925`
+    pub fn with_parent(mut self, parent: &str) -> Self {
+        self.parent = Some(parent.to_string());
+        self
+    }
+
+    /// 只返回`level`级别的地区
+    pub fn with_level(mut self, level: AdminLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// 是否包含废止的地区，使用`region_full.dat`时默认不包含
+    pub fn with_discarded(mut self, include_discarded: bool) -> Self {
+        self.include_discarded = include_discarded;
+        self
+    }
+
+    fn accept(&self, item: &RegionItem) -> bool {
+        if !self.include_discarded && item.discard_year != 0 {
+            return false;
+        }
+        if self
+            .level
+            .is_some_and(|x| x != AdminLevel::of(&item.region_code))
+        {
+            return false;
+        }
+        match &self.parent {
+            Some(parent) => {
+                &item.region_code != parent && item.levels.iter().any(|x| &x.code == parent)
+            }
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct NameTrieNode {
+    children: HashMap<char, usize>,
+    // 名称或者简称在这个节点结束的记录
+    items: Vec<usize>,
+}
+
+/// 按字符组织的名称前缀树，名称、不带类型后缀的名称和自治地方的简称都可以补全
+#[derive(Debug, Clone)]
+pub struct NameTrie {
+    index: Arc<NameIndex>,
+    nodes: Vec<NameTrieNode>,
+}
+
+impl NameTrie {
+    /// 通过名称索引构建，`Region::complete`使用的是`Region`中缓存的索引
+    pub fn new(index: Arc<NameIndex>) -> Self {
+        let mut nodes = vec![NameTrieNode::default()];
+        for (i, aliases) in index.aliases.iter().enumerate() {
+            for alias in aliases {
+                let mut node = 0;
+                for c in alias.chars() {
+                    node = match nodes[node].children.get(&c) {
+                        Some(child) => *child,
+                        None => {
+                            nodes.push(NameTrieNode::default());
+                            let child = nodes.len() - 1;
+                            nodes[node].children.insert(c, child);
+                            child
+                        }
+                    };
+                }
+                nodes[node].items.push(i);
+            }
+        }
+        Self { index, nodes }
+    }
+
+    /// 返回名称以`prefix`开头的地区，包含每一级的名称。名称和`prefix`相同的在前，
+    /// 然后按行政级别、名称的长度和地区码排序
+    pub fn complete(&self, prefix: &str, options: &CompleteOptions) -> Vec<RegionItem> {
+        let mut node = 0;
+        let mut prefix_len = 0;
+        for c in prefix.chars().filter(|c| !c.is_whitespace()) {
+            match self.nodes[node].children.get(&c) {
+                Some(child) => node = *child,
+                None => return Vec::new(),
+            }
+            prefix_len += 1;
+        }
+        if prefix_len == 0 {
+            return Vec::new();
+        }
+        // 每条记录取最短的名称
+        let mut best: HashMap<usize, usize> = HashMap::new();
+        let mut stack = vec![(node, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            for i in &self.nodes[node].items {
+                let extra = best.entry(*i).or_insert(depth);
+                *extra = (*extra).min(depth);
+            }
+            stack.extend(self.nodes[node].children.values().map(|x| (*x, depth + 1)));
+        }
+        let mut res: Vec<(usize, &RegionItem)> = best
+            .into_iter()
+            .map(|(i, extra)| (extra, &self.index.items[i]))
+            .filter(|(_, item)| options.accept(item))
+            .collect();
+        res.sort_by_key(|(extra, item)| {
+            (
+                *extra != 0,
+                AdminLevel::of(&item.region_code),
+                *extra,
+                item.discard_year != 0,
+                item.region_code.clone(),
+            )
+        });
+        res.into_iter()
+            .take(options.limit)
+            .map(|(_, item)| item.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region::Region;
+
+    #[test]
+    fn test_complete() {
+        let region = Region::open("data/region.dat").unwrap();
+        let result = region.complete("临", &CompleteOptions::new(5)).unwrap();
+        assert_eq!(result.len(), 5);
+        assert!(result[..4]
+            .iter()
+            .all(|x| x.matched_level() == AdminLevel::Prefecture));
+        assert!(result.iter().any(|x| x.region_code == "530900"));
+        let result = region.complete("临沧", &CompleteOptions::new(5)).unwrap();
+        assert_eq!(result[0].region_slice, vec!["云南省", "临沧市"]);
+        let options = CompleteOptions::new(10).with_level(AdminLevel::County);
+        assert!(region
+            .complete("临", &options)
+            .unwrap()
+            .iter()
+            .all(|x| x.matched_level() == AdminLevel::County));
+        let options = CompleteOptions::new(10).with_parent("530000");
+        let result = region.complete("双江", &options).unwrap();
+        assert_eq!(result[0].region_code, "530925");
+        let options = CompleteOptions::new(10).with_parent("110000");
+        assert!(region.complete("双江", &options).unwrap().is_empty());
+        assert!(region
+            .complete("", &CompleteOptions::new(5))
+            .unwrap()
+            .is_empty());
+        // 默认不包含废止的地区
+        let region = Region::open("data/region_full.dat").unwrap();
+        assert!(region
+            .complete("崇文", &CompleteOptions::new(5))
+            .unwrap()
+            .is_empty());
+        let options = CompleteOptions::new(5).with_discarded(true);
+        assert_eq!(
+            region.complete("崇文", &options).unwrap()[0].region_code,
+            "110103"
+        );
+    }
+}
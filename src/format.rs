@@ -0,0 +1,522 @@
+//! 数据文件的布局，最初的格式没有文件头，带文件头的格式以`RGCN`开头
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    be_u8_slice_to_i32, decode_u8_list, region::PROVINCE_CODES, source::DataSource, CharOrder,
+    DataFormat, RegionError, RegionItem, RegionLevel, RegionType, SORTED_CHARS_FLAG,
+};
+
+/// 带文件头的格式的前4个字节
+pub(crate) const MAGIC: &[u8; 4] = b"RGCN";
+/// 文件头中的标志位，字符集按GBK编码排序
+pub(crate) const FLAG_SORTED_CHARS: u8 = 1;
+/// 文件头中的标志位，区的偏移之后有4个字节的CRC32校验和，校验文件头之后的所有数据
+pub(crate) const FLAG_CHECKSUM: u8 = 2;
+/// 带文件头的格式至少有省份索引、地级索引和字符集3个区
+pub(crate) const SECTION_COUNT: usize = 3;
+/// 有废止地区码的继承关系时是第4个区
+pub(crate) const SECTION_COUNT_WITH_SUCCESSORS: usize = 4;
+/// v2记录中的标志位，有废止年份
+pub(crate) const RECORD_FLAG_DISCARD_YEAR: u8 = 1;
+/// v2记录中的标志位，有设立年份
+pub(crate) const RECORD_FLAG_ESTABLISHED_YEAR: u8 = 2;
+/// v2之前索引中的偏移占17位
+pub(crate) const MAX_INDEX_OFFSET: u64 = 0x1FFFF;
+
+/// 数据文件中各个区的位置
+#[derive(Debug, Clone)]
+pub(crate) struct Layout {
+    pub(crate) format: DataFormat,
+    pub(crate) version: u32,
+    pub(crate) char_order: CharOrder,
+    pub(crate) records: Range<u64>,
+    pub(crate) province_index: Range<u64>,
+    pub(crate) city_index: Range<u64>,
+    pub(crate) chars: Range<u64>,
+    // 废止地区码的继承关系，只有带文件头的格式可以有
+    pub(crate) successors: Range<u64>,
+    // 文件头中的校验和，最初的格式没有
+    pub(crate) checksum: Option<u32>,
+}
+
+impl Layout {
+    /// 读取文件头，根据前4个字节判断格式
+    pub(crate) fn read(source: &DataSource) -> Result<Self, RegionError> {
+        let file_len = source.len();
+        // 版本号4个字节 + 索引区偏移2个字节
+        if file_len < 6 {
+            return Err(RegionError::CorruptData {
+                offset: file_len,
+                detail: "file is too short to be a region data file".to_string(),
+            });
+        }
+        let mut magic = [0u8; 4];
+        source.read_at(&mut magic, 0)?;
+        if &magic == MAGIC {
+            Self::read_with_header(source)
+        } else {
+            Self::read_legacy(source)
+        }
+    }
+
+    /// 最初的格式：版本号4个字节，索引区偏移2个字节，然后是记录、省份索引和字符集
+    fn read_legacy(source: &DataSource) -> Result<Self, RegionError> {
+        let file_len = source.len();
+        let mut header = [0u8; 6];
+        source.read_at(&mut header, 0)?;
+        let version = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let char_order = if version & SORTED_CHARS_FLAG != 0 {
+            CharOrder::Gbk
+        } else {
+            CharOrder::Unspecified
+        };
+        let version = version & !SORTED_CHARS_FLAG;
+        if version == 0 {
+            return Err(RegionError::UnsupportedVersion(version));
+        }
+        let offset_index = be_u8_slice_to_i32(&header[4..6]) as u64;
+        let char_offset = offset_index + PROVINCE_CODES.len() as u64 * 3;
+        if offset_index < 6 || char_offset > file_len {
+            return Err(RegionError::CorruptData {
+                offset: 4,
+                detail: format!("index offset {offset_index} is out of range"),
+            });
+        }
+        Ok(Self {
+            format: DataFormat::Legacy,
+            version,
+            char_order,
+            records: 6..offset_index,
+            province_index: offset_index..char_offset,
+            city_index: char_offset..char_offset,
+            chars: char_offset..file_len,
+            successors: file_len..file_len,
+            checksum: None,
+        })
+    }
+
+    /// 带文件头的格式(`Indexed`和`V2`)：`RGCN`、格式版本1个字节、标志1个字节、版本号4个字节、
+    /// 区的数量1个字节，然后是每个区的偏移，各4个字节，每个区到下一个区或者文件末尾结束，
+    /// 标志中有`FLAG_CHECKSUM`时最后是4个字节的校验和
+    fn read_with_header(source: &DataSource) -> Result<Self, RegionError> {
+        let file_len = source.len();
+        let mut header = [0u8; 11];
+        source
+            .read_at(&mut header, 0)
+            .map_err(|_| RegionError::CorruptData {
+                offset: file_len,
+                detail: "file is too short to be a region data file".to_string(),
+            })?;
+        let format = match header[4] {
+            1 => DataFormat::Indexed,
+            2 => DataFormat::V2,
+            v => return Err(RegionError::UnsupportedVersion(v as u32)),
+        };
+        let char_order = if header[5] & FLAG_SORTED_CHARS != 0 {
+            CharOrder::Gbk
+        } else {
+            CharOrder::Unspecified
+        };
+        let version = u32::from_be_bytes([header[6], header[7], header[8], header[9]]);
+        if version == 0 {
+            return Err(RegionError::UnsupportedVersion(version));
+        }
+        let section_count = header[10] as usize;
+        let has_checksum = header[5] & FLAG_CHECKSUM != 0;
+        let header_len = 11 + section_count as u64 * 4 + if has_checksum { 4 } else { 0 };
+        if section_count < SECTION_COUNT || header_len > file_len {
+            return Err(RegionError::CorruptData {
+                offset: 10,
+                detail: format!("invalid section count {section_count}"),
+            });
+        }
+        let mut section_bytes = vec![0u8; header_len as usize - 11];
+        source.read_at(&mut section_bytes, 11)?;
+        let checksum = if has_checksum {
+            let checksum = section_bytes.split_off(section_count * 4);
+            Some(u32::from_be_bytes([
+                checksum[0],
+                checksum[1],
+                checksum[2],
+                checksum[3],
+            ]))
+        } else {
+            None
+        };
+        let mut sections = vec![header_len];
+        for (i, chunk) in section_bytes.chunks(4).enumerate() {
+            let offset = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as u64;
+            if offset < sections[i] || offset > file_len {
+                return Err(RegionError::CorruptData {
+                    offset: 11 + i as u64 * 4,
+                    detail: format!("section offset {offset} is out of range"),
+                });
+            }
+            sections.push(offset);
+        }
+        sections.push(file_len);
+        Ok(Self {
+            format,
+            version,
+            char_order,
+            records: sections[0]..sections[1],
+            province_index: sections[1]..sections[2],
+            city_index: sections[2]..sections[3],
+            chars: sections[3]..sections[4],
+            successors: match sections.get(5) {
+                Some(end) => sections[4]..*end,
+                None => file_len..file_len,
+            },
+            checksum,
+        })
+    }
+
+    /// 解析省份索引，返回每个省份记录的范围，从本省的偏移到下一个省份的偏移或者记录区末尾。
+    /// v2之前每个省份3个字节，高7位是省份代码，低17位是记录偏移，必须包含所有省份；
+    /// v2每个省份5个字节，省份代码1个字节，记录偏移4个字节，省份的数量不固定
+    pub(crate) fn read_province_index(
+        &self,
+        source: &DataSource,
+    ) -> Result<HashMap<i32, Range<u64>>, RegionError> {
+        let index_len = self.province_index.end - self.province_index.start;
+        let valid_len = match self.format {
+            DataFormat::V2 => index_len > 0 && index_len.is_multiple_of(5),
+            _ => index_len == PROVINCE_CODES.len() as u64 * 3,
+        };
+        if !valid_len {
+            return Err(RegionError::CorruptData {
+                offset: self.province_index.start,
+                detail: format!("invalid province index length {index_len}"),
+            });
+        }
+        let index_bytes = source.read_range(self.province_index.start, index_len as usize)?;
+        let entries: Vec<(i32, u64)> = match self.format {
+            DataFormat::V2 => index_bytes
+                .chunks(5)
+                .map(|x| (x[0] as i32, be_u8_slice_to_u64(&x[1..])))
+                .collect(),
+            _ => index_bytes
+                .chunks(3)
+                .map(|x| {
+                    let combine = be_u8_slice_to_i32(x);
+                    (combine >> 17, (combine & 0x1FFFF) as u64)
+                })
+                .collect(),
+        };
+        let entry_len = index_len as usize / entries.len();
+        for (i, (code, offset)) in entries.iter().enumerate() {
+            let valid_code = match self.format {
+                DataFormat::V2 => (1..=99).contains(code) && (i == 0 || entries[i - 1].0 < *code),
+                _ => PROVINCE_CODES[i] == *code,
+            };
+            let last_offset = if i == 0 {
+                self.records.start
+            } else {
+                entries[i - 1].1
+            };
+            if !valid_code || *offset < last_offset || *offset >= self.records.end {
+                return Err(RegionError::CorruptData {
+                    offset: self.province_index.start + (i * entry_len) as u64,
+                    detail: format!("invalid index entry for province {code}"),
+                });
+            }
+        }
+        let mut res = HashMap::new();
+        for (i, (code, offset)) in entries.iter().enumerate() {
+            let end = entries.get(i + 1).map(|x| x.1).unwrap_or(self.records.end);
+            res.insert(*code, *offset..end);
+        }
+        Ok(res)
+    }
+
+    /// 解析地级索引，返回每个4位地区码记录的范围，到下一个4位地区码或者本省记录的末尾。
+    /// v2之前每个4位地区码4个字节，高15位是4位地区码，低17位是记录偏移；
+    /// v2每个4位地区码6个字节，4位地区码2个字节，记录偏移4个字节
+    pub(crate) fn read_city_index(
+        &self,
+        source: &DataSource,
+        provinces: &HashMap<i32, Range<u64>>,
+    ) -> Result<HashMap<i32, Range<u64>>, RegionError> {
+        let entry_len = match self.format {
+            DataFormat::V2 => 6,
+            _ => 4,
+        };
+        let index_len = self.city_index.end - self.city_index.start;
+        if !index_len.is_multiple_of(entry_len) {
+            return Err(RegionError::CorruptData {
+                offset: self.city_index.start,
+                detail: format!("invalid city index length {index_len}"),
+            });
+        }
+        let index_bytes = source.read_range(self.city_index.start, index_len as usize)?;
+        let mut entries: Vec<(i32, u64)> = Vec::new();
+        for (i, entry) in index_bytes.chunks(entry_len as usize).enumerate() {
+            let (code, offset) = match self.format {
+                DataFormat::V2 => (
+                    be_u8_slice_to_i32(&entry[..2]),
+                    be_u8_slice_to_u64(&entry[2..]),
+                ),
+                _ => {
+                    let combine = be_u8_slice_to_u64(entry);
+                    ((combine >> 17) as i32, combine & 0x1FFFF)
+                }
+            };
+            let in_province = provinces
+                .get(&(code / 100))
+                .is_some_and(|x| x.contains(&offset));
+            if !in_province || entries.last().is_some_and(|x| x.0 >= code || x.1 >= offset) {
+                return Err(RegionError::CorruptData {
+                    offset: self.city_index.start + i as u64 * entry_len,
+                    detail: format!("invalid city index entry for {code}"),
+                });
+            }
+            entries.push((code, offset));
+        }
+        let mut res = HashMap::new();
+        for (i, (code, offset)) in entries.iter().enumerate() {
+            let province_end = provinces[&(code / 100)].end;
+            let end = entries
+                .get(i + 1)
+                .map(|x| x.1.min(province_end))
+                .unwrap_or(province_end);
+            res.insert(*code, *offset..end);
+        }
+        Ok(res)
+    }
+
+    /// 解析废止地区码的继承关系，每条是4个字节的地区码，1个字节的数量，
+    /// 然后是每个继承的地区码，各4个字节，按地区码排序
+    pub(crate) fn read_successors(
+        &self,
+        source: &DataSource,
+    ) -> Result<HashMap<i32, Vec<i32>>, RegionError> {
+        let data = source.read_range(
+            self.successors.start,
+            (self.successors.end - self.successors.start) as usize,
+        )?;
+        let mut res = HashMap::new();
+        let mut offset = 0;
+        let mut last_code = 0;
+        while offset < data.len() {
+            let entry_offset = self.successors.start + offset as u64;
+            let count = data.get(offset + 4).copied().unwrap_or_default() as usize;
+            let entry = match data.get(offset..offset + 5 + count * 4) {
+                Some(entry) if count > 0 => entry,
+                _ => {
+                    return Err(RegionError::CorruptData {
+                        offset: entry_offset,
+                        detail: "invalid successors entry".to_string(),
+                    })
+                }
+            };
+            let code = be_u8_slice_to_i32(&entry[..4]);
+            let successors: Vec<i32> = entry[5..].chunks(4).map(be_u8_slice_to_i32).collect();
+            let valid = |x: &i32| (100000..=999999).contains(x);
+            if code <= last_code || !valid(&code) || !successors.iter().all(valid) {
+                return Err(RegionError::CorruptData {
+                    offset: entry_offset,
+                    detail: format!("invalid successors of {code}"),
+                });
+            }
+            last_code = code;
+            res.insert(code, successors);
+            offset += 5 + count * 4;
+        }
+        Ok(res)
+    }
+}
+
+/// 数据文件中的一条记录，名称和废止年份还没有解析
+#[derive(Debug, Clone)]
+pub(crate) struct RawRecord<'a> {
+    // 记录在数据文件中的偏移
+    pub(crate) offset: u64,
+    pub(crate) code: i32,
+    pub(crate) region_type: i32,
+    body: &'a [u8],
+    format: DataFormat,
+}
+
+impl RawRecord<'_> {
+    /// 字符索引、废止年份和设立年份，v2之前的格式没有设立年份
+    pub(crate) fn fields(&self) -> (Vec<u32>, u32, u32) {
+        match self.format {
+            DataFormat::V2 => {
+                // 标志1个字节，最低位表示有废止年份，第2位表示有设立年份，
+                // 然后依次是废止年份和设立年份，各2个字节，最后每个字符索引2个字节
+                let flags = self.body.first().copied().unwrap_or_default();
+                let mut chars = self.body.get(1..).unwrap_or_default();
+                let mut years = [0; 2];
+                for (i, flag) in [RECORD_FLAG_DISCARD_YEAR, RECORD_FLAG_ESTABLISHED_YEAR]
+                    .into_iter()
+                    .enumerate()
+                {
+                    if flags & flag != 0 && chars.len() >= 2 {
+                        years[i] = be_u8_slice_to_i32(&chars[..2]) as u32;
+                        chars = &chars[2..];
+                    }
+                }
+                let name_char_index_list = chars
+                    .chunks_exact(2)
+                    .map(|x| be_u8_slice_to_i32(x) as u32)
+                    .collect();
+                (name_char_index_list, years[0], years[1])
+            }
+            _ => {
+                let (name_char_index_list, discard_year_int) = decode_u8_list(self.body);
+                let mut discard_year = 0;
+                if discard_year_int > 0 {
+                    discard_year = discard_year_int + 1980;
+                }
+                (name_char_index_list, discard_year, 0)
+            }
+        }
+    }
+
+    /// 是否是6位的地区码
+    pub(crate) fn has_valid_code(&self) -> bool {
+        (100000..=999999).contains(&self.code)
+    }
+
+    /// 解析成只包含记录本身的结果，`region_slice`为空，
+    /// 地区码不是6位或者字符不在字符集中时返回`RegionError::CorruptData`
+    pub(crate) fn decode(
+        &self,
+        char_map: &HashMap<usize, char>,
+    ) -> Result<RegionItem, RegionError> {
+        if !self.has_valid_code() {
+            return Err(RegionError::CorruptData {
+                offset: self.offset,
+                detail: format!("invalid region code {}", self.code),
+            });
+        }
+        let (name_char_index_list, discard_year, established_year) = self.fields();
+        let mut name_chars = Vec::new();
+        for i in name_char_index_list {
+            match char_map.get(&(i as usize)) {
+                Some(c) => name_chars.push(*c),
+                None => {
+                    return Err(RegionError::CorruptData {
+                        offset: self.offset,
+                        detail: format!("char index {i} of {} is not in the char table", self.code),
+                    })
+                }
+            }
+        }
+        // 类型不存在时按`RegionType::Other`处理，`Region::verify`会报告`UnknownType`
+        let level = RegionLevel::new(
+            self.code.to_string(),
+            String::from_iter(name_chars),
+            RegionType::from_id(self.region_type).unwrap_or_default(),
+        );
+        Ok(RegionItem {
+            region_code: level.code.clone(),
+            name: level.name.clone(),
+            region_slice: Vec::new(),
+            discard_year,
+            established_year,
+            levels: vec![level],
+        })
+    }
+}
+
+/// 依次读取记录。v2之前每条记录是1个字节的大小，3个字节的地区码和类型，然后是名称和废止年份；
+/// v2是2个字节的大小，4个字节的地区码，1个字节的类型，然后是标志、废止年份和名称。
+/// 记录超出范围时返回`RegionError::CorruptData`，然后停止
+pub(crate) struct RecordIter<'a> {
+    data: &'a [u8],
+    // `data`在数据文件中的偏移
+    base: u64,
+    offset: usize,
+    format: DataFormat,
+}
+
+impl<'a> RecordIter<'a> {
+    pub(crate) fn new(data: &'a [u8], base: u64, format: DataFormat) -> Self {
+        Self {
+            data,
+            base,
+            offset: 0,
+            format,
+        }
+    }
+}
+
+impl<'a> Iterator for RecordIter<'a> {
+    type Item = Result<RawRecord<'a>, RegionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset;
+        if offset >= self.data.len() {
+            return None;
+        }
+        let header_len = match self.format {
+            DataFormat::V2 => 7,
+            _ => 4,
+        };
+        let header = self.data.get(offset..offset + header_len);
+        let size = match (header, self.format) {
+            (Some(header), DataFormat::V2) => be_u8_slice_to_i32(&header[..2]) as usize,
+            (Some(header), _) => header[0] as usize,
+            (None, _) => 0,
+        };
+        let (header, body) = match (header, self.data.get(offset + header_len..offset + size)) {
+            (Some(header), Some(body)) if size >= header_len => (header, body),
+            _ => {
+                self.offset = self.data.len();
+                return Some(Err(RegionError::CorruptData {
+                    offset: self.base + offset as u64,
+                    detail: format!("invalid record size {size}"),
+                }));
+            }
+        };
+        self.offset += size;
+        let (code, region_type) = match self.format {
+            DataFormat::V2 => (be_u8_slice_to_i32(&header[2..6]), header[6] as i32),
+            _ => {
+                // 地区码和类型，高20位是地区码，低4位是类型
+                let region_code_type = be_u8_slice_to_i32(&header[1..]);
+                (region_code_type >> 4, region_code_type & 0xF)
+            }
+        };
+        Some(Ok(RawRecord {
+            offset: self.base + offset as u64,
+            code,
+            region_type,
+            body,
+            format: self.format,
+        }))
+    }
+}
+
+/// 大端字节序列转成u64
+fn be_u8_slice_to_u64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |res, b| (res << 8) + *b as u64)
+}
+
+/// CRC32(IEEE)校验和，和zlib的`crc32`一致
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
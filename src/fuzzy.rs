@@ -0,0 +1,242 @@
+//! 模糊搜索地区名称，支持全拼、首字母和有错别字的名称，比如`shuangjiang`、`sj`和`双江县`
+use crate::{name_index::NameIndex, pinyin::readings, AdminLevel, RegionItem};
+
+/// 匹配方式，越靠前越接近
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchKind {
+    /// 和名称或者简称完全相同
+    Exact,
+    /// 和名称或者简称的全拼相同
+    Pinyin,
+    /// 和名称或者简称的拼音首字母相同
+    Initials,
+    /// 是名称或者简称的开头
+    Prefix,
+    /// 是名称或者简称的拼音的开头，或者混用了全拼和首字母
+    PinyinPrefix,
+    /// 和名称或者简称的读音相同，但是有不同的字
+    Homophone,
+    /// 和名称或者简称有少量不同的字
+    Typo,
+}
+
+/// 模糊搜索的结果
+#[derive(Debug, Clone)]
+pub struct FuzzyMatch {
+    /// 匹配到的地区，包含每一级的名称
+    pub region: RegionItem,
+    /// 匹配方式
+    pub kind: MatchKind,
+    /// 差异的大小，`Prefix`和`PinyinPrefix`是没有匹配的字数，`Homophone`和`Typo`是不同的字数
+    pub distance: usize,
+}
+
+/// 在`index`中模糊搜索，只包含字母的按拼音匹配，否则按汉字匹配并且容许错别字。
+/// 结果按匹配方式、差异的大小和行政级别排序，最多返回`limit`个
+pub fn search(index: &NameIndex, query: &str, limit: usize) -> Vec<FuzzyMatch> {
+    let query: String = query
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'')
+        .collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let pinyin = query.chars().all(|c| c.is_ascii_alphabetic());
+    let query_lower = query.to_ascii_lowercase();
+    let query_chars: Vec<char> = query.chars().collect();
+    let mut res: Vec<FuzzyMatch> = Vec::new();
+    for (item, aliases) in index.items.iter().zip(&index.aliases) {
+        let best = aliases
+            .iter()
+            .filter_map(|alias| {
+                if pinyin {
+                    match_pinyin(query_lower.as_bytes(), alias)
+                } else {
+                    match_chars(&query_chars, alias)
+                }
+            })
+            .min();
+        if let Some((kind, distance)) = best {
+            res.push(FuzzyMatch {
+                region: item.clone(),
+                kind,
+                distance,
+            });
+        }
+    }
+    res.sort_by_key(|x| {
+        (
+            x.kind,
+            x.distance,
+            AdminLevel::of(&x.region.region_code),
+            x.region.discard_year != 0,
+            x.region.region_code.clone(),
+        )
+    });
+    res.truncate(limit);
+    res
+}
+
+/// 按汉字匹配，至少2个字，同音字的数量不限，其他错别字的数量不超过1个或者名称长度的三分之一
+fn match_chars(query: &[char], alias: &str) -> Option<(MatchKind, usize)> {
+    let alias: Vec<char> = alias.chars().collect();
+    if query == alias {
+        return Some((MatchKind::Exact, 0));
+    }
+    if query.len() >= 2 && alias.starts_with(query) {
+        return Some((MatchKind::Prefix, alias.len() - query.len()));
+    }
+    if query.len() < 2 {
+        return None;
+    }
+    // 输入法中常见的同音字
+    if query.len() == alias.len()
+        && query
+            .iter()
+            .zip(&alias)
+            .all(|(a, b)| a == b || readings(*a).iter().any(|x| readings(*b).contains(x)))
+    {
+        let distance = query.iter().zip(&alias).filter(|(a, b)| a != b).count();
+        return Some((MatchKind::Homophone, distance));
+    }
+    let max_typos = (query.len().min(alias.len()) / 3).max(1);
+    if query.len().abs_diff(alias.len()) > max_typos {
+        return None;
+    }
+    let distance = edit_distance(query, &alias);
+    (distance <= max_typos).then_some((MatchKind::Typo, distance))
+}
+
+/// 按拼音匹配，每个字可以是全拼或者首字母，最后一个字可以只有拼音的开头
+fn match_pinyin(query: &[u8], alias: &str) -> Option<(MatchKind, usize)> {
+    let chars: Vec<&[&str]> = alias.chars().map(readings).collect();
+    if chars.iter().any(|x| x.is_empty()) {
+        return None;
+    }
+    let mut best = None;
+    match_syllables(query, &chars, (false, false), &mut best);
+    best
+}
+
+/// 依次匹配每个字的读音，`used`记录是否用过全拼和首字母
+fn match_syllables(
+    query: &[u8],
+    chars: &[&[&str]],
+    used: (bool, bool),
+    best: &mut Option<(MatchKind, usize)>,
+) {
+    if query.is_empty() {
+        let kind = match (chars.is_empty(), used) {
+            (true, (true, false)) => MatchKind::Pinyin,
+            (true, (false, true)) => MatchKind::Initials,
+            _ => MatchKind::PinyinPrefix,
+        };
+        let result = Some((kind, chars.len()));
+        if best.is_none() || result < *best {
+            *best = result;
+        }
+        return;
+    }
+    let Some((readings, rest)) = chars.split_first() else {
+        return;
+    };
+    for reading in readings.iter().map(|x| x.as_bytes()) {
+        if let Some(query) = query.strip_prefix(reading) {
+            match_syllables(query, rest, (true, used.1), best);
+        }
+        // zh、ch、sh 可以是1个或者2个字母
+        let initial_len = if reading.len() > 2 && reading[1] == b'h' && b"zcs".contains(&reading[0])
+        {
+            2
+        } else {
+            1
+        };
+        for len in 1..=initial_len {
+            if let Some(query) = query.strip_prefix(&reading[..len]) {
+                match_syllables(query, rest, (used.0, true), best);
+            }
+        }
+        // 拼音的开头只能在最后
+        if query.len() > initial_len && query.len() < reading.len() && reading.starts_with(query) {
+            match_syllables(&[], rest, (true, true), best);
+        }
+    }
+}
+
+/// 两个字符串的编辑距离
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if x == y {
+                previous
+            } else {
+                previous.min(row[j]).min(current) + 1
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region::Region;
+
+    #[test]
+    fn test_search_fuzzy() {
+        let region = Region::open("data/region.dat").unwrap();
+        for query in [
+            "shuangjiang",
+            "sj",
+            "SJX",
+            "shuang jiang",
+            "双江县",
+            "双江拉祜族佤族布朗族傣族治县",
+        ] {
+            let result = region.search_fuzzy(query, 20).unwrap();
+            assert!(
+                result.iter().any(|x| x.region.region_code == "530925"),
+                "{query}"
+            );
+        }
+        let result = region.search_fuzzy("shuangjiang", 5).unwrap();
+        assert_eq!(
+            result[0].region.name,
+            "云南省临沧市双江拉祜族佤族布朗族傣族自治县"
+        );
+        assert_eq!(result[0].kind, MatchKind::Pinyin);
+        // 多音字和行政级别
+        let result = region.search_fuzzy("chongqing", 5).unwrap();
+        assert_eq!(result[0].region.region_code, "500000");
+        assert_eq!(
+            region.search_fuzzy("cs", 1).unwrap()[0].kind,
+            MatchKind::Initials
+        );
+        let result = region.search_fuzzy("临仓", 5).unwrap();
+        assert_eq!(result[0].region.region_code, "530900");
+        assert_eq!(result[0].kind, MatchKind::Homophone);
+        let result = region.search_fuzzy("临沧县", 5).unwrap();
+        assert_eq!(result[0].region.region_code, "530900");
+        assert_eq!(result[0].kind, MatchKind::Typo);
+        assert!(region.search_fuzzy("xyzxyz", 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        let distance = |a: &str, b: &str| {
+            edit_distance(
+                &a.chars().collect::<Vec<_>>(),
+                &b.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(distance("临沧市", "临沧市"), 0);
+        assert_eq!(distance("临仓市", "临沧市"), 1);
+        assert_eq!(distance("临沧", "临沧市"), 1);
+        assert_eq!(distance("", "临沧"), 2);
+    }
+}
@@ -0,0 +1,275 @@
+//! 解析和校验居民身份证号码，前6位通过`Region`查询地区
+use std::fmt;
+
+use crate::{
+    region::{search_with_fallback, Region},
+    RegionError, RegionItem,
+};
+
+// ISO 7064 MOD 11-2 前17位的权重
+const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+
+// 余数对应的校验码
+const CHECK_CODES: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// 性别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    /// 男，顺序码为奇数
+    Male,
+    /// 女，顺序码为偶数
+    Female,
+}
+
+/// 出生日期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BirthDate {
+    /// 年
+    pub year: u32,
+    /// 月
+    pub month: u32,
+    /// 日
+    pub day: u32,
+}
+
+impl fmt::Display for BirthDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// 身份证号码存在的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdCardIssue {
+    /// 出生日期不是有效的日期
+    InvalidBirthDate(String),
+    /// 校验码不正确
+    ChecksumMismatch {
+        /// 根据前17位计算的校验码
+        expected: char,
+        /// 号码中的校验码
+        actual: char,
+    },
+    /// 最新的数据和包含废止地区码的数据中都没有前6位对应的地区
+    UnknownRegion(String),
+    /// 地区码在出生之前的年份已经废止
+    DiscardedBeforeBirth {
+        /// 地区码
+        code: String,
+        /// 废止的年份
+        discard_year: u32,
+        /// 出生的年份
+        birth_year: u32,
+    },
+}
+
+impl fmt::Display for IdCardIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IdCardIssue::InvalidBirthDate(date) => write!(f, "invalid birth date: {}", date),
+            IdCardIssue::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "check digit mismatch: expected {}, actual {}",
+                expected, actual
+            ),
+            IdCardIssue::UnknownRegion(code) => write!(f, "unknown region code: {}", code),
+            IdCardIssue::DiscardedBeforeBirth {
+                code,
+                discard_year,
+                birth_year,
+            } => write!(
+                f,
+                "region code {} was discarded in {}, before the birth year {}",
+                code, discard_year, birth_year
+            ),
+        }
+    }
+}
+
+/// 身份证号码的解析结果
+#[derive(Debug, Clone)]
+pub struct IdCard {
+    /// 18位号码，15位号码会升级成18位，校验码是大写的`X`
+    pub number: String,
+    /// 是否由15位号码升级
+    pub upgraded: bool,
+    /// 前6位对应的地区，没有找到时为`None`
+    pub region: Option<RegionItem>,
+    /// 出生日期，日期无效时为`None`
+    pub birth_date: Option<BirthDate>,
+    /// 性别
+    pub gender: Gender,
+    /// 发现的问题，为空表示号码有效
+    pub issues: Vec<IdCardIssue>,
+}
+
+impl IdCard {
+    /// 解析身份证号码，`region`是最新的数据，`full`是包含废止地区码的数据，
+    /// 前6位在`region`中不存在时再从`full`中查询。
+    /// 长度或字符不正确时返回`RegionError::InvalidCode`，其他问题记录在`issues`中
+    pub fn parse(
+        number: &str,
+        region: &Region,
+        full: Option<&Region>,
+    ) -> Result<Self, RegionError> {
+        let (number, upgraded) = normalize(number)?;
+        let birth_date = parse_birth_date(&number[6..14]);
+        let mut issues = Vec::new();
+        if birth_date.is_none() {
+            issues.push(IdCardIssue::InvalidBirthDate(number[6..14].to_string()));
+        }
+        let expected = check_code(&number[..17]);
+        let actual = number.chars().last().unwrap_or_default();
+        if expected != actual {
+            issues.push(IdCardIssue::ChecksumMismatch { expected, actual });
+        }
+        let code = &number[..6];
+        let region = search_with_fallback(region, full, code)?;
+        match (&region, birth_date) {
+            (None, _) => issues.push(IdCardIssue::UnknownRegion(code.to_string())),
+            (Some(item), Some(date)) if item.discard_year != 0 && item.discard_year < date.year => {
+                issues.push(IdCardIssue::DiscardedBeforeBirth {
+                    code: code.to_string(),
+                    discard_year: item.discard_year,
+                    birth_year: date.year,
+                })
+            }
+            _ => {}
+        }
+        // 第17位是顺序码的最后一位，奇数为男，偶数为女
+        let gender = if (number.as_bytes()[16] - b'0') % 2 == 1 {
+            Gender::Male
+        } else {
+            Gender::Female
+        };
+        Ok(Self {
+            number,
+            upgraded,
+            region,
+            birth_date,
+            gender,
+            issues,
+        })
+    }
+
+    /// 是否没有发现问题
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// 将15位号码升级成18位：出生年份前加`19`，再计算校验码
+pub fn upgrade_15_to_18(number: &str) -> Result<String, RegionError> {
+    if number.len() != 15 || !number.bytes().all(|x| x.is_ascii_digit()) {
+        return Err(RegionError::InvalidCode {
+            input: number.to_string(),
+            reason: "must be 15 digits".to_string(),
+        });
+    }
+    let body = format!("{}19{}", &number[..6], &number[6..]);
+    let check = check_code(&body);
+    Ok(format!("{body}{check}"))
+}
+
+/// 根据前17位计算 ISO 7064 MOD 11-2 校验码
+fn check_code(body: &str) -> char {
+    let sum: u32 = body
+        .bytes()
+        .zip(WEIGHTS)
+        .map(|(b, w)| (b - b'0') as u32 * w)
+        .sum();
+    CHECK_CODES[(sum % 11) as usize]
+}
+
+/// 校验长度和字符，返回18位号码和是否由15位号码升级
+fn normalize(number: &str) -> Result<(String, bool), RegionError> {
+    let number = number.trim();
+    if !number.is_ascii() {
+        return Err(RegionError::InvalidCode {
+            input: number.to_string(),
+            reason: "must be ASCII".to_string(),
+        });
+    }
+    match number.len() {
+        15 => Ok((upgrade_15_to_18(number)?, true)),
+        18 => {
+            let (body, last) = number.split_at(17);
+            if !body.bytes().all(|x| x.is_ascii_digit())
+                || !(last.as_bytes()[0].is_ascii_digit() || last.eq_ignore_ascii_case("x"))
+            {
+                return Err(RegionError::InvalidCode {
+                    input: number.to_string(),
+                    reason: "must be 17 digits followed by a digit or X".to_string(),
+                });
+            }
+            Ok((number.to_ascii_uppercase(), false))
+        }
+        _ => Err(RegionError::InvalidCode {
+            input: number.to_string(),
+            reason: "must be 15 or 18 characters".to_string(),
+        }),
+    }
+}
+
+/// 解析`YYYYMMDD`格式的出生日期
+fn parse_birth_date(date: &str) -> Option<BirthDate> {
+    let year: u32 = date[..4].parse().ok()?;
+    let month: u32 = date[4..6].parse().ok()?;
+    let day: u32 = date[6..].parse().ok()?;
+    let leap = (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if year < 1800 || day == 0 || day > days {
+        return None;
+    }
+    Some(BirthDate { year, month, day })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_card() {
+        let region = Region::open("data/region.dat").unwrap();
+        let full = Region::open("data/region_full.dat").unwrap();
+        let id_card = IdCard::parse("11010519491231002X", &region, Some(&full)).unwrap();
+        assert!(id_card.is_valid());
+        assert_eq!(id_card.region.unwrap().name, "北京市朝阳区");
+        assert_eq!(id_card.birth_date.unwrap().to_string(), "1949-12-31");
+        assert_eq!(id_card.gender, Gender::Female);
+        // 校验码和出生日期不正确
+        let id_card = IdCard::parse("110105194902300021", &region, None).unwrap();
+        assert_eq!(id_card.issues.len(), 2);
+        assert!(id_card.birth_date.is_none());
+        // 废止的地区码需要从包含废止地区码的数据中查询
+        let number = upgrade_15_to_18("110103800101001").unwrap();
+        assert_eq!(number, "110103198001010013");
+        let id_card = IdCard::parse(&number, &region, None).unwrap();
+        assert_eq!(
+            id_card.issues,
+            vec![IdCardIssue::UnknownRegion("110103".to_string())]
+        );
+        let id_card = IdCard::parse("110103800101001", &region, Some(&full)).unwrap();
+        assert!(id_card.upgraded);
+        assert!(id_card.is_valid());
+        assert_eq!(id_card.region.unwrap().name, "北京市崇文区");
+        assert_eq!(id_card.gender, Gender::Male);
+        let id_card = IdCard::parse("110103201101010015", &region, Some(&full)).unwrap();
+        assert!(matches!(
+            id_card.issues[..],
+            [IdCardIssue::DiscardedBeforeBirth {
+                discard_year: 2010,
+                birth_year: 2011,
+                ..
+            }]
+        ));
+        assert!(IdCard::parse("11010519491231002", &region, None).is_err());
+        assert!(IdCard::parse("1101051949123100Y2", &region, None).is_err());
+    }
+}
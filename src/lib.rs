@@ -8,7 +8,7 @@ use std::path::PathBuf;
 
 use region_cn::region::Region;
 
-let mut region = Region::new(PathBuf::from("data/region.dat"));
+let region = Region::new(PathBuf::from("data/region.dat"));
 // 直接在region.dat中搜索
 match region.search_with_data("530925") {
     Ok(data) => {
@@ -27,13 +27,28 @@ assert_eq!(result.name, "云南省临沧市双江拉祜族佤族布朗族傣族
 ```
 */
 
+pub mod address;
+pub mod complete;
+mod format;
+pub mod fuzzy;
+pub mod id_card;
+pub mod name_index;
+mod pinyin;
 pub mod region;
+pub mod region_type;
+mod source;
 pub mod trie;
+pub mod uscc;
+pub mod verify;
+pub mod writer;
 
 use std::{fmt, num::ParseIntError};
 
+pub use region_type::RegionType;
+
 /// RegionItem
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct RegionItem {
     /// 地区代码
     pub region_code: String,
@@ -43,8 +58,131 @@ pub struct RegionItem {
     pub region_slice: Vec<String>,
     /// 废止的年份，为0表示未废止
     pub discard_year: u32,
+    /// 设立的年份，为0表示未知，只有`DataFormat::V2`的数据文件可以记录
+    pub established_year: u32,
+    /// 每一级地区的代码、名称和类型，和`region_slice`一一对应
+    pub levels: Vec<RegionLevel>,
+}
+
+impl RegionItem {
+    /// 实际匹配到的地区码，地区码不存在时是最近的上级的代码
+    pub fn matched_code(&self) -> &str {
+        self.levels
+            .last()
+            .map(|x| x.code.as_str())
+            .unwrap_or(&self.region_code)
+    }
+
+    /// 实际匹配到的地区的行政级别
+    pub fn matched_level(&self) -> AdminLevel {
+        AdminLevel::of(self.matched_code())
+    }
+
+    /// 是否精确匹配到查询的地区码
+    pub fn is_exact(&self) -> bool {
+        self.matched_code() == self.region_code
+    }
+
+    /// 在`year`年是否有效：设立年份未知或者不晚于`year`，并且未废止或者在`year`之后才废止，
+    /// 比如2010年废止的`110103`在2009年有效，在2010年无效
+    pub fn is_valid_in(&self, year: u32) -> bool {
+        (self.established_year == 0 || self.established_year <= year)
+            && (self.discard_year == 0 || year < self.discard_year)
+    }
+}
+
+/// 地区码的匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    /// 地区码必须存在
+    Strict,
+    /// 地区码不存在时返回最近的上级
+    #[default]
+    Ancestor,
+}
+
+/// 行政级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AdminLevel {
+    /// 省级
+    Province,
+    /// 地级
+    Prefecture,
+    /// 县级
+    County,
+}
+
+impl AdminLevel {
+    /// 通过6位地区码判断级别，比如`530000`是省级，`530900`是地级，`530925`和`429004`是县级
+    pub fn of(region_code: &str) -> Self {
+        if region_code.ends_with("0000") {
+            AdminLevel::Province
+        } else if region_code.ends_with("00") {
+            AdminLevel::Prefecture
+        } else {
+            AdminLevel::County
+        }
+    }
+}
+
+/// 一级地区
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionLevel {
+    /// 本级地区代码，比如`530900`
+    pub code: String,
+    /// 不带类型后缀的名称，比如`临沧`
+    pub stem: String,
+    /// 类型，比如`RegionType::City`
+    pub region_type: RegionType,
+    /// 全称，比如`临沧市`
+    pub name: String,
+}
+
+impl RegionLevel {
+    pub(crate) fn new(code: String, stem: String, region_type: RegionType) -> Self {
+        let name = format!("{stem}{}", region_type.suffix());
+        // 数据文件中特别行政区按`区`记录，名称是`香港特别行政`，这里去掉`特别行政区`作为简称
+        let (stem, region_type) = match name.strip_suffix("特别行政区") {
+            Some(short) => (short.to_string(), RegionType::Other),
+            None => (stem, region_type),
+        };
+        Self {
+            code,
+            stem,
+            region_type,
+            name,
+        }
+    }
 }
 
+/// 字符集的排列顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CharOrder {
+    /// 未指定顺序，data/region.py 默认使用`set`生成字符集，每次打包的顺序都可能不同
+    #[default]
+    Unspecified,
+    /// 按GBK编码排序，相同的数据总是生成相同的文件
+    Gbk,
+}
+
+/// 数据文件的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataFormat {
+    /// 最初的格式，没有文件头，只有省份索引，data/region.py 生成的就是这种格式
+    #[default]
+    Legacy,
+    /// 带`RGCN`文件头的格式，增加了4位地区码的索引，查询时只需要解析省级和所在地级的记录
+    Indexed,
+    /// 带`RGCN`文件头和4位地区码索引的第2版格式，字段都按字节对齐并且更宽：
+    /// 偏移4个字节，字符索引2个字节，废止年份2个字节，类型1个字节，
+    /// 省份索引的长度不固定，字符集使用UTF-8编码。类型和地区码的字段只是预留了宽度，
+    /// 仍然只支持`RegionType`中的类型和6位地区码，不能写入乡镇级的地区码和新的类型后缀
+    V2,
+}
+
+/// 版本号的最高位表示字符集按GBK编码排序，按时间生成的版本号不会用到这一位
+pub(crate) const SORTED_CHARS_FLAG: u32 = 1 << 31;
+
 /// 大端字节序列转成i32
 pub(crate) fn be_u8_slice_to_i32(bytes: &[u8]) -> i32 {
     let mut res = 0;
@@ -56,7 +194,7 @@ pub(crate) fn be_u8_slice_to_i32(bytes: &[u8]) -> i32 {
 }
 
 /// 将vec[u8]解析成12位的数组
-pub(crate) fn decode_u8_list(u8_list: &Vec<u8>) -> (Vec<u32>, u32) {
+pub(crate) fn decode_u8_list(u8_list: &[u8]) -> (Vec<u32>, u32) {
     // 按4bit分割， 再3个组合成12位
     let mut four_bits: Vec<u8> = Vec::new();
     for u8_val in u8_list {
@@ -75,22 +213,56 @@ pub(crate) fn decode_u8_list(u8_list: &Vec<u8>) -> (Vec<u32>, u32) {
         }
     }
 
-    let mut discard_year_int: u32 = 0;
-    let last_four_bits = &four_bits[res.len() * 3..];
-    for (i, &b) in last_four_bits.iter().enumerate() {
-        discard_year_int += (b as u32) << (4 * (last_four_bits.len() - i - 1));
-    }
+    // 数据损坏时剩下的位可能超过32位，只保留最后的
+    let discard_year_int = four_bits[res.len() * 3..]
+        .iter()
+        .fold(0u32, |res, b| (res << 4) | *b as u32);
 
     (res, discard_year_int)
 }
 
 /// Wrapper for Error
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum RegionError {
     /// IOError
     IOError(std::io::Error),
-    /// ParseIntError
+    /// ParseError
     ParseError(ParseIntError),
+    /// 没有找到地区码或名称对应的记录
+    NotFound {
+        /// 查询的地区码或名称
+        code: String,
+    },
+    /// 地区码格式不正确
+    InvalidCode {
+        /// 传入的地区码
+        input: String,
+        /// 原因
+        reason: String,
+    },
+    /// 数据文件中没有这个省份
+    UnknownProvince(u8),
+    /// 数据文件损坏或者不是地区数据文件
+    CorruptData {
+        /// 出错的位置
+        offset: u64,
+        /// 详细信息
+        detail: String,
+    },
+    /// 不支持的数据文件版本
+    UnsupportedVersion(u32),
+    /// 地区码在指定的年份还没有设立或者已经废止
+    NotValidInYear {
+        /// 查询的地区码
+        code: String,
+        /// 查询的年份
+        year: u32,
+        /// 设立的年份，为0表示未知
+        established_year: u32,
+        /// 废止的年份，为0表示未废止
+        discard_year: u32,
+    },
     /// Message
     Message(String),
 }
@@ -100,9 +272,50 @@ impl fmt::Display for RegionError {
         match self {
             RegionError::IOError(err) => write!(f, "IOError: {}", err),
             RegionError::ParseError(err) => write!(f, "ParseError: {}", err),
+            RegionError::NotFound { code } => write!(f, "cannot find record: {}", code),
+            RegionError::InvalidCode { input, reason } => {
+                write!(f, "invalid region code {:?}: {}", input, reason)
+            }
+            RegionError::UnknownProvince(code) => write!(f, "unknown province: {}", code),
+            RegionError::CorruptData { offset, detail } => {
+                write!(f, "corrupt data at offset {}: {}", offset, detail)
+            }
+            RegionError::UnsupportedVersion(version) => {
+                write!(f, "unsupported version: {}", version)
+            }
+            RegionError::NotValidInYear {
+                code,
+                year,
+                established_year,
+                discard_year,
+            } => write!(
+                f,
+                "region code {} is not valid in {} (established: {}, discarded: {})",
+                code, year, established_year, discard_year
+            ),
             RegionError::Message(msg) => write!(f, "Error: {}", msg),
         }
     }
 }
 
-impl std::error::Error for RegionError {}
+impl std::error::Error for RegionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RegionError::IOError(err) => Some(err),
+            RegionError::ParseError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RegionError {
+    fn from(err: std::io::Error) -> Self {
+        RegionError::IOError(err)
+    }
+}
+
+impl From<ParseIntError> for RegionError {
+    fn from(err: ParseIntError) -> Self {
+        RegionError::ParseError(err)
+    }
+}
@@ -0,0 +1,274 @@
+//! 通过地区名称反查地区代码
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{RegionItem, RegionType};
+
+// 少数民族名称，用于从自治地方的名称中得到简称，比如`双江拉祜族佤族布朗族傣族自治县`简称`双江县`
+const ETHNIC_NAMES: [&str; 55] = [
+    "蒙古",
+    "回",
+    "藏",
+    "维吾尔",
+    "苗",
+    "彝",
+    "壮",
+    "布依",
+    "朝鲜",
+    "满",
+    "侗",
+    "瑶",
+    "白",
+    "土家",
+    "哈尼",
+    "哈萨克",
+    "傣",
+    "黎",
+    "傈僳",
+    "佤",
+    "畲",
+    "高山",
+    "拉祜",
+    "水",
+    "东乡",
+    "纳西",
+    "景颇",
+    "柯尔克孜",
+    "土",
+    "达斡尔",
+    "仫佬",
+    "羌",
+    "布朗",
+    "撒拉",
+    "毛南",
+    "仡佬",
+    "锡伯",
+    "阿昌",
+    "普米",
+    "塔吉克",
+    "怒",
+    "乌孜别克",
+    "俄罗斯",
+    "鄂温克",
+    "德昂",
+    "保安",
+    "裕固",
+    "京",
+    "塔塔尔",
+    "独龙",
+    "鄂伦春",
+    "赫哲",
+    "门巴",
+    "珞巴",
+    "基诺",
+];
+
+// 由多个字组成的类型后缀和去掉后缀的简称至少需要的字数，比如`浦东新区`简称`浦东`。
+// `林区`前面的字通常是名称的一部分(比如`万柏林区`)，只有`神农架林区`这样至少3个字的才去掉
+const COMPOUND_SUFFIXES: [(&str, usize); 5] = [
+    ("新区", 2),
+    ("工矿区", 2),
+    ("矿区", 2),
+    ("特区", 2),
+    ("林区", 3),
+];
+
+/// 地区名称索引，支持全称、带上级的部分名称以及不带类型后缀的简称
+#[derive(Debug, Clone, Default)]
+pub struct NameIndex {
+    pub(crate) items: Vec<RegionItem>,
+    // 每条记录的别名，第一个是本级的全称
+    pub(crate) aliases: Vec<Vec<String>>,
+    // 每条记录的上级，省份在前
+    pub(crate) ancestors: Vec<Vec<usize>>,
+    pub(crate) alias_map: HashMap<String, Vec<usize>>,
+}
+
+impl NameIndex {
+    /// 通过`get_record_from_data`的结果构建索引
+    pub fn new(records: &[RegionItem]) -> Self {
+        let code_map: HashMap<&str, usize> = records
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.region_code.as_str(), i))
+            .collect();
+        let mut index = NameIndex::default();
+        for (i, record) in records.iter().enumerate() {
+            let code = &record.region_code;
+            let mut ancestors = Vec::new();
+            if code.len() == 6 {
+                for parent in [format!("{}0000", &code[..2]), format!("{}00", &code[..4])] {
+                    if &parent == code {
+                        continue;
+                    }
+                    if let Some(j) = code_map.get(parent.as_str()) {
+                        ancestors.push(*j);
+                    }
+                }
+            }
+            let mut region_slice: Vec<String> =
+                ancestors.iter().map(|j| records[*j].name.clone()).collect();
+            region_slice.push(record.name.clone());
+            let levels = ancestors
+                .iter()
+                .chain([&i])
+                .flat_map(|j| records[*j].levels.clone())
+                .collect();
+            let aliases = name_aliases(&record.name);
+            for alias in &aliases {
+                index.alias_map.entry(alias.clone()).or_default().push(i);
+            }
+            index.items.push(RegionItem {
+                region_code: code.clone(),
+                name: region_slice.join(""),
+                region_slice,
+                discard_year: record.discard_year,
+                established_year: record.established_year,
+                levels,
+            });
+            index.aliases.push(aliases);
+            index.ancestors.push(ancestors);
+        }
+        index
+    }
+
+    /// 通过名称查找，返回所有匹配的地区，未废止的在前
+    pub fn search(&self, name: &str) -> Vec<RegionItem> {
+        let name: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut matched = BTreeSet::new();
+        for (i, _) in name.char_indices() {
+            if let Some(candidates) = self.alias_map.get(&name[i..]) {
+                for idx in candidates {
+                    if self.match_ancestors(&name[..i], &self.ancestors[*idx]) {
+                        matched.insert(*idx);
+                    }
+                }
+            }
+        }
+        let mut res: Vec<&RegionItem> = matched.into_iter().map(|i| &self.items[i]).collect();
+        res.sort_by_key(|x| (x.discard_year != 0, x.region_code.clone()));
+        res.into_iter().cloned().collect()
+    }
+
+    /// 判断`prefix`是否由上级名称依次组成，每一级都可以省略
+    fn match_ancestors(&self, prefix: &str, ancestors: &[usize]) -> bool {
+        if prefix.is_empty() {
+            return true;
+        }
+        for (j, ancestor) in ancestors.iter().enumerate().rev() {
+            for alias in &self.aliases[*ancestor] {
+                if let Some(rest) = prefix.strip_suffix(alias.as_str()) {
+                    if self.match_ancestors(rest, &ancestors[..j]) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// 获取名称的别名，包括全称、去掉类型后缀的名称以及自治地方的简称
+fn name_aliases(name: &str) -> Vec<String> {
+    let mut res = vec![name.to_string()];
+    let (stem, suffix) = match name.strip_suffix("特别行政区") {
+        Some(stem) => (stem, "特别行政区"),
+        None => {
+            let (stem, region_type) = RegionType::classify(name);
+            (stem, region_type.suffix())
+        }
+    };
+    if stem.chars().count() >= 2 && stem != name {
+        res.push(stem.to_string());
+    }
+    if let Some(short) = COMPOUND_SUFFIXES.iter().find_map(|(suffix, min_chars)| {
+        name.strip_suffix(suffix)
+            .filter(|x| x.chars().count() >= *min_chars)
+    }) {
+        res.push(short.to_string());
+    }
+    if name.contains("自治") {
+        let stem = stem.trim_end_matches("自治");
+        let short = ETHNIC_NAMES
+            .iter()
+            .filter_map(|x| stem.find(x))
+            .filter(|i| stem[..*i].chars().count() >= 2)
+            .min()
+            .map(|i| &stem[..i]);
+        if let Some(short) = short {
+            res.push(short.to_string());
+            // 双江县、湘西州、莫力达瓦旗
+            if let Some(generic) = suffix.chars().last().filter(|x| "县州旗".contains(*x)) {
+                res.push(format!("{short}{generic}"));
+            }
+        }
+    }
+    res.dedup();
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegionLevel;
+
+    fn item(code: &str, name: &str, discard_year: u32) -> RegionItem {
+        RegionItem {
+            region_code: code.to_string(),
+            name: name.to_string(),
+            region_slice: Vec::new(),
+            discard_year,
+            established_year: 0,
+            levels: vec![RegionLevel::new(
+                code.to_string(),
+                name.to_string(),
+                RegionType::Other,
+            )],
+        }
+    }
+
+    #[test]
+    fn test_name_index() {
+        let index = NameIndex::new(&[
+            item("110000", "北京市", 0),
+            item("110105", "朝阳区", 0),
+            item("220000", "吉林省", 0),
+            item("220100", "长春市", 0),
+            item("220104", "朝阳区", 0),
+            item("420000", "湖北省", 0),
+            item("429004", "仙桃市", 0),
+            item("530000", "云南省", 0),
+            item("530900", "临沧市", 0),
+            item("530925", "双江拉祜族佤族布朗族傣族自治县", 0),
+        ]);
+        let result = index.search("云南省临沧市双江拉祜族佤族布朗族傣族自治县");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].region_code, "530925");
+        assert_eq!(
+            result[0].region_slice,
+            vec!["云南省", "临沧市", "双江拉祜族佤族布朗族傣族自治县"]
+        );
+        assert_eq!(
+            result[0]
+                .levels
+                .iter()
+                .map(|x| x.code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["530000", "530900", "530925"]
+        );
+        assert_eq!(index.search("临沧市双江县")[0].region_code, "530925");
+        assert_eq!(index.search("云南双江")[0].region_code, "530925");
+        assert_eq!(index.search("双江拉祜族佤族布朗族傣族自治县").len(), 1);
+        let result = index.search("朝阳区");
+        assert_eq!(
+            result.iter().map(|x| x.name.as_str()).collect::<Vec<_>>(),
+            vec!["北京市朝阳区", "吉林省长春市朝阳区"]
+        );
+        assert_eq!(index.search("长春朝阳区")[0].region_code, "220104");
+        assert_eq!(
+            index.search("湖北仙桃")[0].region_slice,
+            vec!["湖北省", "仙桃市"]
+        );
+        assert!(index.search("北京市长春市朝阳区").is_empty());
+        assert!(index.search("临沧市云南省").is_empty());
+    }
+}
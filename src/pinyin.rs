@@ -0,0 +1,364 @@
+//! 数据文件中的汉字的拼音，用于按拼音和首字母搜索地区名称
+//!
+//! 由 ICU 的`Han-Latin`转换生成并去掉了声调，`ü`写作`v`；多音字补充了地名中的读音，比如`长`、`六`、`番`
+use std::{collections::HashMap, sync::OnceLock};
+
+// 拼音和对应的汉字，多音字在每个读音中都出现
+const PINYIN_TABLE: [(&str, &str); 339] = [
+    ("a", "阿"),
+    ("ai", "爱"),
+    ("an", "安岸庵鞍"),
+    ("ang", "昂"),
+    ("ao", "敖澳"),
+    ("ba", "八坝巴灞霸鲅"),
+    ("bai", "拜柏白百"),
+    ("ban", "办半坂板版班"),
+    ("bang", "蚌"),
+    ("bao", "保包堡宝"),
+    ("bei", "北碑碚贝陂"),
+    ("ben", "本"),
+    ("beng", "蚌"),
+    ("bi", "壁比毕泌濞璧碧"),
+    ("bian", "边"),
+    ("bie", "别"),
+    ("bin", "宾彬滨"),
+    ("bing", "兵秉"),
+    ("bo", "亳伯勃博播柏波"),
+    ("bu", "埔埠堡布步部"),
+    ("cai", "彩蔡"),
+    ("cang", "仓沧苍藏"),
+    ("cao", "曹草"),
+    ("ce", "册策"),
+    ("cen", "岑"),
+    ("ceng", "曾"),
+    ("cha", "察岔查茶"),
+    ("chai", "柴"),
+    ("chan", "瀍禅"),
+    ("chang", "厂场常昌长阊"),
+    ("chao", "巢朝潮"),
+    ("che", "车"),
+    ("chen", "辰郴陈"),
+    ("cheng", "呈城成承澄盛称"),
+    ("chi", "坻池茌赤"),
+    ("chong", "充冲崇重"),
+    ("chou", "畴"),
+    ("chu", "处楚滁"),
+    ("chuan", "川船"),
+    ("chun", "春淳"),
+    ("ci", "慈次磁"),
+    ("cong", "丛从枞"),
+    ("cui", "翠"),
+    ("cun", "村"),
+    ("cuo", "措错"),
+    ("da", "大达"),
+    ("dai", "代傣大岱带戴"),
+    ("dan", "丹儋单石郸"),
+    ("dang", "党宕当砀"),
+    ("dao", "刀岛稻道"),
+    ("de", "地得德的"),
+    ("deng", "灯登磴等邓"),
+    ("di", "底棣滴迪"),
+    ("dian", "垫店淀点电甸"),
+    ("diao", "调"),
+    ("die", "叠蝶迭"),
+    ("ding", "丁定顶鼎"),
+    ("dong", "东侗动峒洞"),
+    ("dou", "斗都"),
+    ("du", "度杜渡独都"),
+    ("duan", "端"),
+    ("dui", "堆"),
+    ("dun", "敦"),
+    ("duo", "多掇"),
+    ("e", "峨鄂阿额鹅"),
+    ("en", "恩"),
+    ("er", "二儿尔洱"),
+    ("fa", "法"),
+    ("fan", "樊烦番繁范"),
+    ("fang", "坊房放方芳邡防"),
+    ("fei", "妃肥费"),
+    ("fen", "分汾芬"),
+    ("feng", "丰凤奉封峰烽锋风"),
+    ("fu", "佛复孚富府扶抚浮涪福符罘芙阜附"),
+    ("ga", "伽嘎噶"),
+    ("gai", "改盖"),
+    ("gan", "干感甘赣"),
+    ("gang", "冈刚岗港钢"),
+    ("gao", "皋藁高"),
+    ("ge", "个仡各合戈格盖葛阁革"),
+    ("gen", "根"),
+    ("geng", "耿"),
+    ("gong", "公共功宫工巩弓恭拱珙贡"),
+    ("gou", "句沟"),
+    ("gu", "古固姑故沽谷鼓"),
+    ("gua", "瓜"),
+    ("guai", "拐"),
+    ("guan", "关冠官灌管莞观馆"),
+    ("guang", "光广"),
+    ("gui", "归桂贵"),
+    ("guo", "国果涡郭"),
+    ("ha", "哈"),
+    ("hai", "海"),
+    ("han", "含寒汉涵罕邗邯韩"),
+    ("hang", "杭行"),
+    ("hao", "好浩濠"),
+    ("he", "合和河禾荷菏褐贺赫鹤"),
+    ("hei", "黑"),
+    ("heng", "亨恒横衡"),
+    ("hong", "宏洪红虹"),
+    ("hou", "侯后"),
+    ("hu", "互呼壶户湖祜胡葫虎鄠"),
+    ("hua", "化华桦滑花骅"),
+    ("huai", "怀槐淮"),
+    ("huan", "桓环"),
+    ("huang", "凰晃湟潢煌皇黄"),
+    ("hui", "会回徽惠晖汇珲辉"),
+    ("hun", "浑珲"),
+    ("huo", "获霍"),
+    ("ji", "冀即及吉基奇暨极汲济积稷级绩蓟集鸡"),
+    ("jia", "伽佳加嘉夹家架茄贾迦郏"),
+    ("jian", "剑尖建涧犍监碱简箭间"),
+    ("jiang", "姜将江疆绛虹"),
+    ("jiao", "交椒焦礁胶蕉蛟觉角郊"),
+    ("jie", "介揭界结节街解"),
+    ("jin", "晋津缙进金锦"),
+    ("jing", "井京旌景泾竞精经荆镜靖静"),
+    ("jiu", "久九旧酒鸠"),
+    ("ju", "句居巨莒车"),
+    ("juan", "鄄"),
+    ("jue", "觉角"),
+    ("jun", "军君均峻浚"),
+    ("ka", "卡喀"),
+    ("kai", "凯开"),
+    ("kan", "坎"),
+    ("kang", "康"),
+    ("kao", "考"),
+    ("ke", "克可岢柯科"),
+    ("ken", "垦"),
+    ("kong", "崆"),
+    ("kou", "口"),
+    ("ku", "库"),
+    ("kuai", "会"),
+    ("kuan", "宽"),
+    ("kuang", "矿"),
+    ("kui", "奎"),
+    ("kun", "坤昆"),
+    ("la", "喇拉腊"),
+    ("lai", "崃来涞莱赉"),
+    ("lan", "兰岚澜蓝"),
+    ("lang", "廊朗浪琅蒗郎阆"),
+    ("lao", "乐佬崂老"),
+    ("le", "乐"),
+    ("lei", "勒垒类耒雷"),
+    ("leng", "冷棱楞"),
+    ("li", "丽傈利力历李栗梨溧澧犁理礼离立荔蠡醴里鲤黎"),
+    ("lian", "廉涟濂联莲连"),
+    ("liang", "两凉梁良"),
+    ("liao", "聊辽"),
+    ("lie", "列烈"),
+    ("lin", "临林蔺邻麟"),
+    ("ling", "令凌岭灵酃陵零"),
+    ("liu", "六柳流浏留"),
+    ("long", "陇隆龙"),
+    ("lou", "娄楼"),
+    ("lu", "六卢庐泸渌潞炉碌禄芦路陆鲁鹿麓"),
+    ("luan", "峦栾滦"),
+    ("lun", "仑伦轮"),
+    ("luo", "洛漯罗萝"),
+    ("lv", "吕旅绿"),
+    ("lve", "略"),
+    ("ma", "玛马麻"),
+    ("mai", "迈麦"),
+    ("man", "曼满"),
+    ("mang", "芒茫"),
+    ("mao", "毛茂茅"),
+    ("mei", "梅湄眉美"),
+    ("men", "们门"),
+    ("meng", "勐孟梦盟蒙"),
+    ("mi", "密弥汨泌米"),
+    ("mian", "冕勉棉沔渑绵"),
+    ("miao", "苗"),
+    ("min", "岷敏民闵闽"),
+    ("ming", "名明鸣"),
+    ("mo", "墨末漠磨莫谟默"),
+    ("mou", "牟谋"),
+    ("mu", "仫木沐牟牡牧穆"),
+    ("na", "纳那"),
+    ("nai", "乃奈"),
+    ("nan", "南"),
+    ("nang", "囊"),
+    ("nao", "淖"),
+    ("ne", "讷"),
+    ("nei", "内"),
+    ("nen", "嫩"),
+    ("ni", "尼"),
+    ("nian", "年碾"),
+    ("nie", "聂"),
+    ("ning", "宁"),
+    ("niu", "牛"),
+    ("nong", "农"),
+    ("nu", "怒"),
+    ("nuo", "诺"),
+    ("ou", "区瓯"),
+    ("pai", "牌"),
+    ("pan", "攀潘番盘磐"),
+    ("pang", "庞"),
+    ("pei", "沛"),
+    ("peng", "彭蓬"),
+    ("pi", "皮邳郫陂"),
+    ("pian", "偏"),
+    ("piao", "票"),
+    ("ping", "凭坪屏平萍"),
+    ("po", "坡泊繁鄱陂颇"),
+    ("pu", "仆埔堡普浦濮莆蒲谱"),
+    ("qi", "七其启圻奇岐戚旗杞栖淇祁綦耆蕲起麒齐"),
+    ("qia", "卡恰"),
+    ("qian", "乾前千潜谦迁钱铅阡黔"),
+    ("qiang", "强羌"),
+    ("qiao", "巧桥硚谯"),
+    ("qie", "且伽"),
+    ("qin", "勤沁秦覃钦"),
+    ("qing", "庆晴清箐青"),
+    ("qiong", "琼邛"),
+    ("qiu", "丘邱"),
+    ("qu", "区曲朐渠衢"),
+    ("quan", "全劝圈权泉"),
+    ("que", "确"),
+    ("qun", "群"),
+    ("rang", "壤让"),
+    ("rao", "饶"),
+    ("ren", "仁任"),
+    ("ri", "日"),
+    ("rong", "容榕荣蓉融"),
+    ("rou", "柔"),
+    ("ru", "乳如汝"),
+    ("rui", "瑞芮"),
+    ("run", "润"),
+    ("ruo", "若"),
+    ("sa", "撒萨"),
+    ("sai", "塞赛"),
+    ("san", "三"),
+    ("sang", "桑"),
+    ("se", "色"),
+    ("sha", "厦沙砂莎"),
+    ("shai", "色"),
+    ("shan", "单善山汕鄯陕"),
+    ("shang", "上商尚汤"),
+    ("shao", "召绍邵韶"),
+    ("she", "什射歙涉畲社"),
+    ("shen", "什审沈深申神莘"),
+    ("sheng", "升圣嵊生盛省胜"),
+    ("shi", "事什十始峙市师施氏浉狮石"),
+    ("shou", "寿手首"),
+    ("shu", "墅曙束树沭熟疏舒蜀"),
+    ("shuang", "双"),
+    ("shui", "水"),
+    ("shun", "顺"),
+    ("shuo", "朔硕"),
+    ("si", "四寺思斯泗"),
+    ("song", "嵩松淞"),
+    ("su", "僳宿肃苏"),
+    ("sui", "濉睢穗绥遂随"),
+    ("sun", "孙"),
+    ("suo", "索莎"),
+    ("ta", "塔漯"),
+    ("tai", "台太泰"),
+    ("tan", "坛滩潭炭覃郯"),
+    ("tang", "唐堂塘棠汤"),
+    ("tao", "桃洮陶"),
+    ("te", "特"),
+    ("teng", "滕腾藤"),
+    ("ti", "提"),
+    ("tian", "天田"),
+    ("tiao", "调"),
+    ("tie", "铁"),
+    ("ting", "亭汀町"),
+    ("tong", "同桐潼通铜"),
+    ("tou", "头"),
+    ("tu", "吐图土徒涂突"),
+    ("tuan", "团"),
+    ("tun", "屯"),
+    ("tuo", "妥托拖脱陀"),
+    ("wa", "佤洼瓦"),
+    ("wai", "外"),
+    ("wan", "万宛湾畹"),
+    ("wang", "旺望汪王"),
+    ("wei", "为伟位卫围圩委威尉尾巍微未渭潍维蔚魏"),
+    ("wen", "文汶温闻"),
+    ("weng", "瓮翁"),
+    ("wo", "卧斡沃涡"),
+    ("wu", "乌五伍务吴吾婺巫悟无梧武舞芜"),
+    ("xi", "习喜息昔歙浠淅溪细西锡隰"),
+    ("xia", "下厦夏峡辖霞"),
+    ("xian", "仙县咸献贤鲜"),
+    ("xiang", "乡厢向响湘相祥翔芗襄象镶项香"),
+    ("xiao", "孝小猇萧霄"),
+    ("xie", "解谢"),
+    ("xin", "信心忻新莘辛"),
+    ("xing", "兴星杏荥行邢陉"),
+    ("xiong", "雄"),
+    ("xiu", "休修宿岫秀"),
+    ("xu", "叙圩徐溆盱许"),
+    ("xuan", "宣玄"),
+    ("xue", "穴薛"),
+    ("xun", "寻循旬浔浚逊"),
+    ("ya", "亚崖涯牙琊雅鸭"),
+    ("yan", "偃兖堰岩延彦晏沿炎烟焉燕盐研砚郾鄢铅阎雁"),
+    ("yang", "央扬杨洋漾羊阳"),
+    ("yao", "姚尧瑶耀要遥"),
+    ("ye", "业冶叶掖邺野"),
+    ("yi", "义仪伊依夷宜峄弋彝易沂猗益眙翼谊邑驿黟"),
+    ("yin", "印殷荫鄞银阴音"),
+    ("ying", "应盈英荥营蓥迎颍鹰"),
+    ("yong", "埇庸永邕雍"),
+    ("you", "友右尤攸油游犹邮酉"),
+    ("yu", "于余域宇尉屿峪榆渝玉盂禹禺舆蔚虞裕豫郁隅雨鱼"),
+    ("yuan", "元原员园垣沅源苑袁远"),
+    ("yue", "乐岳月越"),
+    ("yun", "云匀员筠蕴运郓郧"),
+    ("za", "扎杂"),
+    ("zai", "载"),
+    ("zan", "赞"),
+    ("zang", "藏"),
+    ("zao", "枣"),
+    ("ze", "则泽"),
+    ("zeng", "增"),
+    ("zha", "扎札柞查闸"),
+    ("zhai", "寨"),
+    ("zhan", "沾湛站"),
+    ("zhang", "丈张彰樟漳章长"),
+    ("zhao", "召招昭朝照肇诏赵"),
+    ("zhe", "哲柘浙"),
+    ("zhen", "圳振浈真贞镇"),
+    ("zheng", "征政正蒸郑"),
+    ("zhi", "峙志指枝植沚治直织脂至芝芷陟"),
+    ("zhong", "中仲忠重钟"),
+    ("zhou", "周州洲舟"),
+    ("zhu", "主助柱株珠祝竹诸驻"),
+    ("zhuang", "壮庄"),
+    ("zhun", "准"),
+    ("zhuo", "卓涿"),
+    ("zi", "子孜梓淄滋秭紫自资"),
+    ("zong", "宗枞"),
+    ("zou", "邹"),
+    ("zu", "族足"),
+    ("zui", "嘴"),
+    ("zun", "遵"),
+    ("zuo", "作左柞"),
+];
+
+/// 汉字的所有读音，不在拼音表中时返回空的列表
+pub(crate) fn readings(c: char) -> &'static [&'static str] {
+    static READINGS: OnceLock<HashMap<char, Vec<&'static str>>> = OnceLock::new();
+    READINGS
+        .get_or_init(|| {
+            let mut map: HashMap<char, Vec<&'static str>> = HashMap::new();
+            for (pinyin, chars) in PINYIN_TABLE {
+                for c in chars.chars() {
+                    map.entry(c).or_default().push(pinyin);
+                }
+            }
+            map
+        })
+        .get(&c)
+        .map_or(&[], |x| x.as_slice())
+}
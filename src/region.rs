@@ -1,27 +1,51 @@
 //! Region search implement
 
 use std::{
-    cell::RefCell,
+    borrow::Cow,
     collections::HashMap,
     fs::File,
     io::{Read, Seek},
+    ops::Range,
     path::PathBuf,
-    rc::Rc,
+    sync::{Arc, OnceLock},
 };
 
 use encoding::{all::GBK, Encoding};
 
-use crate::{be_u8_slice_to_i32, decode_u8_list, trie::RegionTrie, RegionError, RegionItem};
+use crate::{
+    address::{AddressParser, ParsedAddress},
+    complete::{CompleteOptions, NameTrie},
+    format::{crc32, Layout, RecordIter},
+    fuzzy::{self, FuzzyMatch},
+    name_index::NameIndex,
+    source::DataSource,
+    trie::RegionTrie,
+    verify::{VerifyIssue, VerifyReport},
+    AdminLevel, CharOrder, DataFormat, MatchMode, RegionError, RegionItem, RegionType,
+};
 
+/// 地区搜索，实现了`Send`和`Sync`，可以放到`Arc`中在多个线程同时查询
 #[derive(Debug)]
 pub struct Region {
-    file_path: PathBuf,
     version: String,
-    offset_index: u64,
-    region_trier: Option<Rc<RefCell<RegionTrie>>>,
-    char_map: Rc<RefCell<HashMap<usize, char>>>,
-    file: Rc<RefCell<File>>,
-    index_offset_map: HashMap<i32, u64>,
+    char_order: CharOrder,
+    data_format: DataFormat,
+    // 记录区的范围
+    records: Range<u64>,
+    // 文件头中的校验和
+    checksum: Option<u32>,
+    region_trier: OnceLock<RegionTrie>,
+    name_index: OnceLock<Arc<NameIndex>>,
+    address_parser: OnceLock<AddressParser>,
+    name_trie: OnceLock<NameTrie>,
+    char_map: HashMap<usize, char>,
+    source: DataSource,
+    // 省份记录的范围，从本省的偏移到下一个省份的偏移或者索引区
+    index_offset_map: HashMap<i32, Range<u64>>,
+    // 4位地区码记录的范围，最初的格式没有这个索引
+    city_offset_map: HashMap<i32, Range<u64>>,
+    // 废止地区码的继承关系
+    successor_map: HashMap<i32, Vec<i32>>,
 }
 
 impl Default for RegionTrie {
@@ -31,272 +55,628 @@ impl Default for RegionTrie {
 }
 
 // 省份前2位
-const PROVINCE_CODES: [i32; 34] = [
+pub(crate) const PROVINCE_CODES: [i32; 34] = [
     11, 12, 13, 14, 15, 21, 22, 23, 31, 32, 33, 34, 35, 36, 37, 41, 42, 43, 44, 45, 46, 50, 51, 52,
     53, 54, 61, 62, 63, 64, 65, 71, 81, 82,
 ];
 
 impl Region {
+    /// 打开数据文件，文件不存在或格式不正确时会panic，建议使用[`Region::open`]
     pub fn new(file_path: PathBuf) -> Self {
-        let file = File::open(&file_path).unwrap();
-        let mut index_offset_map = HashMap::new();
-        for (i, v) in PROVINCE_CODES.iter().enumerate() {
-            index_offset_map.insert(*v, (i * 3) as u64);
+        Self::open(file_path).unwrap()
+    }
+
+    /// 打开数据文件，并校验文件头、索引区、字符集以及每条记录的大小和字符索引
+    pub fn open<P: Into<PathBuf>>(file_path: P) -> Result<Self, RegionError> {
+        let file = File::open(file_path.into())?;
+        Self::from_source(DataSource::from_file(file)?)
+    }
+
+    /// 内置的最新数据 data/region.dat，需要开启`embed-latest` feature
+    #[cfg(feature = "embed-latest")]
+    pub fn embedded_latest() -> Self {
+        static REGION_LATEST: &[u8] = include_bytes!("../data/region.dat");
+        Self::from_bytes(REGION_LATEST).unwrap()
+    }
+
+    /// 内置的包含废止地区码的数据 data/region_full.dat，需要开启`embed-full` feature
+    #[cfg(feature = "embed-full")]
+    pub fn embedded_full() -> Self {
+        static REGION_FULL: &[u8] = include_bytes!("../data/region_full.dat");
+        Self::from_bytes(REGION_FULL).unwrap()
+    }
+
+    /// 将数据文件映射到内存中，查询时直接从映射的内存中解析记录，不需要系统调用和复制，
+    /// 数据按需加载，内存占用和文件搜索一样低。需要开启`mmap` feature
+    ///
+    /// 映射期间数据文件不能被修改或截断，否则查询结果是未定义的
+    #[cfg(feature = "mmap")]
+    pub fn open_mmap<P: Into<PathBuf>>(file_path: P) -> Result<Self, RegionError> {
+        let file = File::open(file_path.into())?;
+        // Safety: 数据文件是只读使用的，调用方需要保证映射期间文件不被修改
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_source(DataSource::Mmap(mmap))
+    }
+
+    /// 从内存中的数据创建，可以传入`&'static [u8]`或`Vec<u8>`，查询时不会访问文件系统
+    pub fn from_bytes<B: Into<Cow<'static, [u8]>>>(bytes: B) -> Result<Self, RegionError> {
+        Self::from_source(DataSource::Bytes(bytes.into()))
+    }
+
+    /// 从实现了`Read`和`Seek`的数据创建，比如`Cursor<Vec<u8>>`，
+    /// 每次读取都需要加锁，多个线程同时查询时建议使用[`Region::from_bytes`]
+    pub fn from_reader<R: Read + Seek + Send + 'static>(reader: R) -> Result<Self, RegionError> {
+        Self::from_source(DataSource::from_reader(reader)?)
+    }
+
+    /// 校验内存中的数据文件，不需要先打开：文件头、索引区或字符集不正确时返回错误，
+    /// 记录的问题都在`VerifyReport`中，包括打开时会被拒绝的超出范围的记录和字符索引
+    pub fn verify_bytes(bytes: &[u8]) -> Result<VerifyReport, RegionError> {
+        Self::load(DataSource::Bytes(Cow::Owned(bytes.to_vec())))?.verify()
+    }
+
+    /// 从数据来源读取文件头、索引区和字符集，并检查所有记录
+    fn from_source(source: DataSource) -> Result<Self, RegionError> {
+        let region = Self::load(source)?;
+        // 截断的文件的字符集仍然可以解码，需要遍历一次记录，检查大小和字符索引
+        let record_bytes = region.source.read_range(
+            region.records.start,
+            (region.records.end - region.records.start) as usize,
+        )?;
+        for raw in RecordIter::new(&record_bytes, region.records.start, region.data_format) {
+            let raw = raw?;
+            let fields = raw.fields().0;
+            if let Some(index) = fields
+                .into_iter()
+                .find(|x| !region.char_map.contains_key(&(*x as usize)))
+            {
+                return Err(RegionError::CorruptData {
+                    offset: raw.offset,
+                    detail: format!(
+                        "char index {index} of {} is not in the char table",
+                        raw.code
+                    ),
+                });
+            }
         }
-        Self {
-            file_path,
-            version: String::new(),
-            offset_index: 0,
-            region_trier: None,
-            char_map: Rc::new(RefCell::new(HashMap::new())),
-            file: Rc::new(RefCell::new(file)),
-            index_offset_map,
+        Ok(region)
+    }
+
+    /// 读取文件头、索引区和字符集，不检查记录
+    fn load(source: DataSource) -> Result<Self, RegionError> {
+        let layout = Layout::read(&source)?;
+        let index_offset_map = layout.read_province_index(&source)?;
+        let city_offset_map = layout.read_city_index(&source, &index_offset_map)?;
+        let successor_map = layout.read_successors(&source)?;
+        // 字符集
+        let char_offset = layout.chars.start;
+        let char_bytes =
+            source.read_range(char_offset, (layout.chars.end - char_offset) as usize)?;
+        // v2的字符集是UTF-8编码，之前的是GBK编码
+        let chars = match layout.format {
+            DataFormat::V2 => String::from_utf8(char_bytes.to_vec()).map_err(|x| x.to_string()),
+            _ => GBK
+                .decode(&char_bytes, encoding::DecoderTrap::Strict)
+                .map_err(|x| x.to_string()),
+        }
+        .map_err(|detail| RegionError::CorruptData {
+            offset: char_offset,
+            detail,
+        })?;
+        if chars.is_empty() {
+            return Err(RegionError::CorruptData {
+                offset: char_offset,
+                detail: "char table is empty".to_string(),
+            });
         }
+        let mut char_map = HashMap::new();
+        // v2之前前64个值保留给废止年份
+        let char_base = match layout.format {
+            DataFormat::V2 => 0,
+            _ => 64,
+        };
+        for (i, c) in chars.chars().enumerate() {
+            char_map.insert(i + char_base, c);
+        }
+        Ok(Self {
+            version: layout.version.to_string(),
+            char_order: layout.char_order,
+            data_format: layout.format,
+            checksum: layout.checksum,
+            records: layout.records,
+            region_trier: OnceLock::new(),
+            name_index: OnceLock::new(),
+            address_parser: OnceLock::new(),
+            name_trie: OnceLock::new(),
+            char_map,
+            source,
+            index_offset_map,
+            city_offset_map,
+            successor_map,
+        })
     }
 
     /// 构建前缀树
-    fn create_trier(&mut self) -> Result<RegionTrie, RegionError> {
+    fn create_trier(&self) -> Result<RegionTrie, RegionError> {
         let mut trier = RegionTrie::new();
-        self.get_record_from_data()?
-            .iter()
-            .for_each(|x| trier.insert(x.region_code.clone(), x.name.clone(), x.discard_year));
+        self.get_record_from_data()?.into_iter().for_each(|mut x| {
+            trier.insert_level(x.levels.remove(0), x.discard_year, x.established_year)
+        });
         Ok(trier)
     }
 
-    /// 设置字符集map
-    fn set_char_map(&self, file_ref: &mut File) -> Result<(), RegionError> {
-        let mut char_map_ref = self.char_map.borrow_mut();
-        if char_map_ref.is_empty() {
-            // 字符集
-            file_ref
-                .seek(std::io::SeekFrom::Start(self.offset_index + 34 * 3))
-                .map_err(RegionError::IOError)?;
-            // gbk编码
-            let mut char_bytes = Vec::new();
-            file_ref
-                .read_to_end(&mut char_bytes)
-                .map_err(RegionError::IOError)?;
-            let chars = GBK
-                .decode(&char_bytes, encoding::DecoderTrap::Strict)
-                .map_err(|x| RegionError::Message(x.to_string()))?;
-            let mut char_map = HashMap::new();
-            for (i, c) in chars.chars().enumerate() {
-                char_map.insert(i + 64, c);
-            }
-            *char_map_ref = char_map;
-        }
-        Ok(())
-    }
-
-    /// 从 region.dat读取数据记录
-    pub fn get_record_from_data(&mut self) -> Result<Vec<RegionItem>, RegionError> {
-        let mut file = self.file.borrow_mut();
-        // 跳过版本号
-        if self.offset_index == 0 {
-            file.seek(std::io::SeekFrom::Start(4))
-                .map_err(RegionError::IOError)?;
-            let mut index_offset: [u8; 2] = [0; 2];
-            file.read_exact(&mut index_offset)
-                .map_err(RegionError::IOError)?;
-            self.offset_index = be_u8_slice_to_i32(&index_offset) as u64;
-        } else {
-            file.seek(std::io::SeekFrom::Start(6))
-                .map_err(RegionError::IOError)?;
-        }
-        let mut record = vec![0u8; (self.offset_index - 6) as usize];
-        file.read_exact(&mut record).map_err(RegionError::IOError)?;
-        self.set_char_map(&mut file)?;
-        let char_map = self.char_map.borrow();
+    /// 从 region.dat读取数据记录，`region_slice`为空，`levels`只包含记录本身
+    pub fn get_record_from_data(&self) -> Result<Vec<RegionItem>, RegionError> {
+        let record = self.source.read_range(
+            self.records.start,
+            (self.records.end - self.records.start) as usize,
+        )?;
         let mut res = Vec::new();
-        while !record.is_empty() {
-            let size = be_u8_slice_to_i32(&record[..1]);
-            let region_code_type = be_u8_slice_to_i32(&record[1..4]);
-            let region = region_code_type >> 4;
-            let region_type = region_code_type % region;
-            let record_bytes: Vec<u8> = record
-                .iter()
-                .skip(4)
-                .take((size - 4) as usize)
-                .copied()
-                .collect();
-            let (name_char_index_list, discard_year_int) = decode_u8_list(&record_bytes);
-            let mut name_chars = Vec::new();
-            for i in name_char_index_list {
-                name_chars.push(char_map.get(&(i as usize)).unwrap());
-            }
-            let mut name = String::from_iter(name_chars);
-            let mut discard_year = 0;
-            if discard_year_int > 0 {
-                discard_year = discard_year_int + 1980;
-            }
-            name = format!("{name}{}", self.get_type_name(region_type));
-            res.push(RegionItem {
-                region_code: region.to_string(),
-                name,
-                region_slice: Vec::new(),
-                discard_year,
-            });
-            record = record.iter().skip(size as usize).copied().collect();
+        for raw in RecordIter::new(&record, self.records.start, self.data_format) {
+            res.push(raw?.decode(&self.char_map)?);
         }
         Ok(res)
     }
 
     /// 获取区域类型名称
+    #[deprecated(note = "please use `RegionType::from_id` and `RegionType::suffix`")]
     pub fn get_type_name(&self, t: i32) -> String {
-        match t {
-            1 => String::from("省"),
-            2 => String::from("自治区"),
-            3 => String::from("市"),
-            4 => String::from("区"),
-            5 => String::from("县"),
-            6 => String::from("自治县"),
-            7 => String::from("旗"),
-            8 => String::from("盟"),
-            9 => String::from("州"),
-            10 => String::from("自治州"),
-            11 => String::from("藏族自治州"),
-            12 => String::from("满族自治县"),
-            13 => String::from("蒙古族自治县"),
-            14 => String::from("苗族自治县"),
-            15 => String::from("土家族自治县"),
-            _ => String::new(),
+        RegionType::from_id(t)
+            .map(|x| x.suffix())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// 字符集，按在数据文件中的顺序排列
+    pub fn char_table(&self) -> Vec<char> {
+        let mut chars: Vec<(&usize, &char)> = self.char_map.iter().collect();
+        chars.sort();
+        chars.into_iter().map(|x| *x.1).collect()
+    }
+
+    /// 字符集的排列顺序
+    pub fn char_order(&self) -> CharOrder {
+        self.char_order
+    }
+
+    /// 校验数据文件：遍历所有记录，检查记录的大小和偏移是否超出范围、字符索引是否在字符集中、
+    /// 地区码是否按顺序排列并且在所属省份的范围内，以及文件头中的校验和。
+    /// 发现的问题记录在结果中，只有读取失败时才返回错误。记录超出范围或者字符索引不在字符集中的文件
+    /// 打开时就会返回错误，需要通过[`Region::verify_bytes`]校验
+    pub fn verify(&self) -> Result<VerifyReport, RegionError> {
+        let mut report = VerifyReport {
+            format: self.data_format,
+            record_count: 0,
+            checksum_matched: None,
+            issues: Vec::new(),
+        };
+        // 校验和包括文件头之后的所有数据
+        if let Some(expected) = self.checksum {
+            let data = self.source.read_range(
+                self.records.start,
+                (self.source.len() - self.records.start) as usize,
+            )?;
+            let actual = crc32(&data);
+            report.checksum_matched = Some(actual == expected);
+            if actual != expected {
+                report
+                    .issues
+                    .push(VerifyIssue::ChecksumMismatch { expected, actual });
+            }
         }
+        let record = self.source.read_range(
+            self.records.start,
+            (self.records.end - self.records.start) as usize,
+        )?;
+        let mut last_code: Option<i32> = None;
+        for raw in RecordIter::new(&record, self.records.start, self.data_format) {
+            let raw = match raw {
+                Ok(raw) => raw,
+                Err(RegionError::CorruptData { offset, detail }) => {
+                    report
+                        .issues
+                        .push(VerifyIssue::RecordOutOfBounds { offset, detail });
+                    break;
+                }
+                Err(err) => return Err(err),
+            };
+            report.record_count += 1;
+            let (offset, code) = (raw.offset, raw.code.to_string());
+            if !raw.has_valid_code() {
+                report.issues.push(VerifyIssue::InvalidCode {
+                    offset,
+                    code: code.clone(),
+                });
+            }
+            if let Some(previous) = last_code.filter(|x| *x >= raw.code) {
+                report.issues.push(VerifyIssue::Unsorted {
+                    offset,
+                    code: code.clone(),
+                    previous: previous.to_string(),
+                });
+            }
+            last_code = Some(raw.code);
+            let in_province = self
+                .index_offset_map
+                .get(&(raw.code / 10000))
+                .is_some_and(|x| x.contains(&offset));
+            if !in_province {
+                report.issues.push(VerifyIssue::OutOfProvince {
+                    offset,
+                    code: code.clone(),
+                });
+            }
+            let in_city = self
+                .city_offset_map
+                .get(&(raw.code / 100))
+                .is_some_and(|x| x.contains(&offset));
+            if self.data_format != DataFormat::Legacy && !in_city {
+                report.issues.push(VerifyIssue::OutOfCity {
+                    offset,
+                    code: code.clone(),
+                });
+            }
+            if RegionType::from_id(raw.region_type).is_none() {
+                report.issues.push(VerifyIssue::UnknownType {
+                    offset,
+                    code: code.clone(),
+                    region_type: raw.region_type,
+                });
+            }
+            let (name_char_index_list, _, _) = raw.fields();
+            for index in name_char_index_list {
+                if !self.char_map.contains_key(&(index as usize)) {
+                    report.issues.push(VerifyIssue::UnknownChar {
+                        offset,
+                        code: code.clone(),
+                        index,
+                    });
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// 数据文件的格式
+    pub fn data_format(&self) -> DataFormat {
+        self.data_format
     }
 
     /// 获取数据版本号
-    pub fn get_version(&mut self) -> Result<&str, RegionError> {
-        if !self.version.is_empty() {
-            return Ok(&self.version);
-        }
-        let mut file = File::open(&self.file_path).map_err(RegionError::IOError)?;
-        let mut version_bytes: [u8; 4] = [0; 4];
-        file.read_exact(&mut version_bytes)
-            .map_err(RegionError::IOError)?;
-        self.version = i32::from_be_bytes(version_bytes).to_string();
+    pub fn get_version(&self) -> Result<&str, RegionError> {
         Ok(&self.version)
     }
 
-    /// 从region.dat搜索数据
-    pub fn search_with_data(&mut self, region_code: &str) -> Result<RegionItem, RegionError> {
-        let mut file = self.file.borrow_mut();
-        if region_code.len() != 6 {
-            return Err(RegionError::Message(
-                "region_code's length must be 6".to_string(),
-            ));
-        }
-        if self.offset_index == 0 {
-            file.seek(std::io::SeekFrom::Start(4))
-                .map_err(RegionError::IOError)?;
-            let mut index_offset: [u8; 2] = [0; 2];
-            file.read_exact(&mut index_offset)
-                .map_err(RegionError::IOError)?;
-            self.offset_index = be_u8_slice_to_i32(&index_offset) as u64;
-        }
-        // 字符集
-        self.set_char_map(&mut file)?;
-        // 查找索引区
-        file.seek(std::io::SeekFrom::Start(self.offset_index))
-            .map_err(RegionError::IOError)?;
-        let mut region_code_offset: [u8; 3] = [0u8; 3];
-        let region_code_int: i32 = region_code.parse().map_err(RegionError::ParseError)?;
+    /// 从region.dat搜索数据，地区码不存在时返回最近的上级
+    pub fn search_with_data(&self, region_code: &str) -> Result<RegionItem, RegionError> {
+        self.search_with_data_mode(region_code, MatchMode::Ancestor)
+    }
+
+    /// 按指定的匹配方式从region.dat搜索数据
+    pub fn search_with_data_mode(
+        &self,
+        region_code: &str,
+        mode: MatchMode,
+    ) -> Result<RegionItem, RegionError> {
+        check_region_code(region_code)?;
+        let region_code_int: i32 = region_code.parse()?;
         // region_code 前2位
         let code_2_int = region_code_int / 10000;
-        match self.index_offset_map.get(&code_2_int) {
-            Some(v) => {
-                file.seek(std::io::SeekFrom::Start(self.offset_index + (*v)))
-                    .map_err(RegionError::IOError)?;
-            }
+        // 查找省份记录的范围
+        let province_range = match self.index_offset_map.get(&code_2_int) {
+            Some(v) => v.clone(),
             None => {
-                return Err(RegionError::Message("cannot find record".to_string()));
+                return Err(RegionError::UnknownProvince(code_2_int as u8));
             }
-        }
-        let _ = file
-            .read(&mut region_code_offset)
-            .map_err(RegionError::IOError)?;
-        let combine = be_u8_slice_to_i32(&region_code_offset);
-        let offset = combine - (code_2_int << 17);
-        file.seek(std::io::SeekFrom::Start(offset as u64))
-            .map_err(RegionError::IOError)?;
-        let mut province_record: [u8; 4000] = [0u8; 4000];
-        let _ = file
-            .read(&mut province_record)
-            .map_err(RegionError::IOError)?;
+        };
+        // 有4位地区码的索引时只需要解析省级和所在地级的记录
+        let ranges = match self.data_format {
+            DataFormat::Legacy => vec![province_range],
+            _ => {
+                let code_4_int = region_code_int / 100;
+                let mut ranges: Vec<Range<u64>> = self
+                    .city_offset_map
+                    .get(&(code_2_int * 100))
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                if code_4_int % 100 != 0 {
+                    ranges.extend(self.city_offset_map.get(&code_4_int).cloned());
+                }
+                ranges
+            }
+        };
         let search_codes = [
-            format!("{}0000", &region_code[..2]),
-            format!("{}00", &region_code[..4]),
-            region_code.to_string(),
+            code_2_int * 10000,
+            region_code_int / 100 * 100,
+            region_code_int,
         ];
-        let mut region_slice = Vec::new();
-        let mut offset = 0;
+        let mut levels = Vec::new();
         let mut discard_year = 0;
-        let char_map = self.char_map.borrow();
-        while offset < 4000 {
-            let size = be_u8_slice_to_i32(&province_record[offset..1 + offset]);
-            let region_code_type = be_u8_slice_to_i32(&province_record[1 + offset..4 + offset]);
-            let region = region_code_type >> 4;
-            // 已经到别的省份
-            if region / 10000 != region_code_int / 10000 {
-                break;
-            }
-            let region_type = region_code_type % region;
-            if search_codes.contains(&region.to_string()) {
-                let record_bytes: Vec<u8> = province_record
-                    .iter()
-                    .skip(4 + offset)
-                    .take((size - 4) as usize)
-                    .copied()
-                    .collect();
-                let (name_char_index_list, discard_year_int) = decode_u8_list(&record_bytes);
-                let mut name_chars = Vec::new();
-                for i in name_char_index_list {
-                    name_chars.push(char_map.get(&(i as usize)).unwrap());
+        let mut established_year = 0;
+        for range in ranges {
+            let record = self
+                .source
+                .read_range(range.start, (range.end - range.start) as usize)?;
+            for raw in RecordIter::new(&record, range.start, self.data_format) {
+                let raw = raw?;
+                if !search_codes.contains(&raw.code) {
+                    continue;
                 }
-                let mut name = String::from_iter(name_chars);
-                if discard_year_int > 0 && region.to_string() == region_code {
-                    discard_year = discard_year_int + 1980;
-                }
-                name = format!("{name}{}", self.get_type_name(region_type));
-                region_slice.push(name);
+                // 废止年份和设立年份是匹配到的最后一级的
+                let mut record = raw.decode(&self.char_map)?;
+                discard_year = record.discard_year;
+                established_year = record.established_year;
+                levels.append(&mut record.levels);
             }
-            offset += size as usize;
-        }
-        if region_slice.is_empty() {
-            return Err(RegionError::Message("cannot find record".to_string()));
         }
-        Ok(RegionItem {
+        let region_slice: Vec<String> = levels.iter().map(|x| x.name.clone()).collect();
+        let item = RegionItem {
             region_code: region_code.to_string(),
             name: region_slice.join(""),
             region_slice,
             discard_year,
+            established_year,
+            levels,
+        };
+        if item.levels.is_empty() || (mode == MatchMode::Strict && !item.is_exact()) {
+            return Err(RegionError::NotFound {
+                code: region_code.to_string(),
+            });
+        }
+        Ok(item)
+    }
+
+    /// 通过前缀树来搜索结果，地区码不存在时返回最近的上级
+    pub fn search_with_trie(&self, region_code: &str) -> Result<RegionItem, RegionError> {
+        self.search_with_trie_mode(region_code, MatchMode::Ancestor)
+    }
+
+    /// 按指定的匹配方式通过前缀树来搜索结果
+    pub fn search_with_trie_mode(
+        &self,
+        region_code: &str,
+        mode: MatchMode,
+    ) -> Result<RegionItem, RegionError> {
+        check_region_code(region_code)?;
+        self.trier()?.search_with_mode(region_code, mode)
+    }
+
+    /// 查询地区码在`year`年的记录，地区码必须存在，在这一年还没有设立或者已经废止时返回
+    /// `RegionError::NotValidInYear`，比如`110103`在2009年有效，在2010年无效
+    pub fn search_as_of(&self, region_code: &str, year: u32) -> Result<RegionItem, RegionError> {
+        let item = self.search_with_trie_mode(region_code, MatchMode::Strict)?;
+        if !item.is_valid_in(year) {
+            return Err(RegionError::NotValidInYear {
+                code: region_code.to_string(),
+                year,
+                established_year: item.established_year,
+                discard_year: item.discard_year,
+            });
+        }
+        Ok(item)
+    }
+
+    /// 在`year`年有效的所有地区，按地区码排序，包含每一级的名称
+    pub fn iter_valid_in(
+        &self,
+        year: u32,
+    ) -> Result<impl Iterator<Item = RegionItem> + '_, RegionError> {
+        let trier = self.trier()?;
+        Ok(self
+            .get_record_from_data()?
+            .into_iter()
+            .filter(move |x| x.is_valid_in(year))
+            .filter_map(|x| {
+                trier
+                    .search_with_mode(&x.region_code, MatchMode::Strict)
+                    .ok()
+            }))
+    }
+
+    /// 废止的地区码由哪些地区继承，比如`110103`北京市崇文区由`110101`东城区继承，
+    /// 数据文件中没有记录时返回空的列表，可以通过[`Region::suggest_successors`]推测
+    pub fn successors(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        let item = self.search_with_trie_mode(region_code, MatchMode::Strict)?;
+        let code: i32 = item.region_code.parse()?;
+        let trier = self.trier()?;
+        self.successor_map
+            .get(&code)
+            .into_iter()
+            .flatten()
+            .map(|x| trier.search_with_mode(&x.to_string(), MatchMode::Strict))
+            .collect()
+    }
+
+    /// 根据地区码推测废止地区的继承地区，用于补充继承关系：优先是前4位相同的同级未废止地区，
+    /// 同名的在前并按地区码的差值排序，其他的按地区码排序；没有时是同一省份中同名的同级未废止地区。
+    /// 结果只是推测，比如`110103`返回的第一个是`110101`，但是`110104`实际由`110102`继承。
+    /// 地区码未废止时返回空的列表
+    pub fn suggest_successors(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        let item = self.search_with_trie_mode(region_code, MatchMode::Strict)?;
+        if item.discard_year == 0 {
+            return Ok(Vec::new());
+        }
+        let code: i32 = item.region_code.parse()?;
+        let stem = item
+            .levels
+            .last()
+            .map(|x| x.stem.clone())
+            .unwrap_or_default();
+        let trier = self.trier()?;
+        let candidates: Vec<RegionItem> = self
+            .get_record_from_data()?
+            .into_iter()
+            .filter(|x| x.discard_year == 0 && x.region_code != item.region_code)
+            .filter(|x| AdminLevel::of(&x.region_code) == AdminLevel::of(&item.region_code))
+            .collect();
+        let same_stem = |x: &RegionItem| x.levels.last().is_some_and(|x| x.stem == stem);
+        let mut res: Vec<&RegionItem> = candidates
+            .iter()
+            .filter(|x| x.region_code[..4] == item.region_code[..4])
+            .collect();
+        if res.is_empty() {
+            res = candidates
+                .iter()
+                .filter(|x| x.region_code[..2] == item.region_code[..2] && same_stem(x))
+                .collect();
+        }
+        // 不同名的通常并入了地区码较小的中心城区，按地区码排序
+        res.sort_by_key(|x| {
+            let distance = if same_stem(x) {
+                x.region_code
+                    .parse::<i32>()
+                    .unwrap_or_default()
+                    .abs_diff(code)
+            } else {
+                0
+            };
+            (!same_stem(x), distance, x.region_code.clone())
+        });
+        res.into_iter()
+            .map(|x| trier.search_with_mode(&x.region_code, MatchMode::Strict))
+            .collect()
+    }
+
+    /// 通过名称反查地区，支持全称、带上级的部分名称以及不带类型后缀的简称，
+    /// 比如`临沧市双江县`，有重名时返回所有匹配的地区
+    pub fn search_by_name(&self, name: &str) -> Result<Vec<RegionItem>, RegionError> {
+        let res = self.name_index()?.search(name);
+        if res.is_empty() {
+            return Err(RegionError::NotFound {
+                code: name.to_string(),
+            });
+        }
+        Ok(res)
+    }
+
+    /// 模糊搜索地区名称，支持全拼、首字母和有错别字的名称，比如`shuangjiang`、`sj`和`双江县`，
+    /// 结果按匹配方式、差异的大小和行政级别排序，最多返回`limit`个
+    pub fn search_fuzzy(&self, query: &str, limit: usize) -> Result<Vec<FuzzyMatch>, RegionError> {
+        Ok(fuzzy::search(self.name_index()?, query, limit))
+    }
+
+    /// 按名称前缀补全地区，用于输入时的提示，比如`临`返回`临沧市`等，包含每一级的名称，
+    /// 可以通过`CompleteOptions`限制上级和行政级别，默认不包含废止的地区
+    pub fn complete(
+        &self,
+        prefix: &str,
+        options: &CompleteOptions,
+    ) -> Result<Vec<RegionItem>, RegionError> {
+        let trie = match self.name_trie.get() {
+            Some(trie) => trie,
+            None => {
+                let trie = NameTrie::new(self.name_index()?.clone());
+                self.name_trie.get_or_init(|| trie)
+            }
+        };
+        Ok(trie.complete(prefix, options))
+    }
+
+    /// 从地址文本中解析地区，返回匹配到的地区、匹配的范围、剩下的文本和置信度，
+    /// 比如`北京市东城区xx路`解析出`110101`和`xx路`。没有匹配到任何地区时返回`RegionError::NotFound`
+    pub fn parse_address(&self, address: &str) -> Result<ParsedAddress, RegionError> {
+        let parser = match self.address_parser.get() {
+            Some(parser) => parser,
+            None => {
+                let parser = AddressParser::new(self.name_index()?.clone());
+                self.address_parser.get_or_init(|| parser)
+            }
+        };
+        parser.parse(address).ok_or_else(|| RegionError::NotFound {
+            code: address.to_string(),
         })
     }
 
-    /// 通过前缀树来搜索结果
-    pub fn search_with_trie(&mut self, region_code: &str) -> Result<RegionItem, RegionError> {
-        if region_code.len() != 6 {
-            return Err(RegionError::Message(
-                "region_code's length must be 6".to_string(),
-            ));
+    /// 所有的省级地区
+    pub fn provinces(&self) -> Result<Vec<RegionItem>, RegionError> {
+        Ok(self.trier()?.provinces())
+    }
+
+    /// 下一级地区，省直辖的县级地区(比如`429004`仙桃市)作为省的下一级
+    pub fn children(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        check_region_code(region_code)?;
+        self.trier()?.children(region_code)
+    }
+
+    /// 上一级地区，省级地区返回`None`
+    pub fn parent(&self, region_code: &str) -> Result<Option<RegionItem>, RegionError> {
+        check_region_code(region_code)?;
+        self.trier()?.parent(region_code)
+    }
+
+    /// 所有上级地区，省级在前
+    pub fn ancestors(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        check_region_code(region_code)?;
+        self.trier()?.ancestors(region_code)
+    }
+
+    /// 同一上级下的其他地区
+    pub fn siblings(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        check_region_code(region_code)?;
+        self.trier()?.siblings(region_code)
+    }
+
+    /// 获取名称索引，第一次调用时构建
+    fn name_index(&self) -> Result<&Arc<NameIndex>, RegionError> {
+        if let Some(index) = self.name_index.get() {
+            return Ok(index);
         }
-        if self.region_trier.is_none() {
-            let trier = self.create_trier().unwrap();
-            self.region_trier = Some(Rc::new(RefCell::new(trier)));
+        let index = Arc::new(NameIndex::new(&self.get_record_from_data()?));
+        Ok(self.name_index.get_or_init(|| index))
+    }
+
+    /// 获取前缀树，第一次调用时构建
+    fn trier(&self) -> Result<&RegionTrie, RegionError> {
+        if let Some(trier) = self.region_trier.get() {
+            return Ok(trier);
+        }
+        let trier = self.create_trier()?;
+        Ok(self.region_trier.get_or_init(|| trier))
+    }
+}
+
+/// 精确查询地区码，在`region`中不存在时再从包含废止地区码的`full`中查询，都不存在时返回`None`
+pub(crate) fn search_with_fallback(
+    region: &Region,
+    full: Option<&Region>,
+    region_code: &str,
+) -> Result<Option<RegionItem>, RegionError> {
+    for region in std::iter::once(region).chain(full) {
+        match region.search_with_data_mode(region_code, MatchMode::Strict) {
+            Ok(item) => return Ok(Some(item)),
+            Err(RegionError::NotFound { .. })
+            | Err(RegionError::UnknownProvince(_))
+            | Err(RegionError::InvalidCode { .. }) => {}
+            Err(e) => return Err(e),
         }
-        self.region_trier
-            .clone()
-            .unwrap()
-            .borrow()
-            .search(region_code)
     }
+    Ok(None)
+}
+
+/// 检查地区码是否为6位数字
+fn check_region_code(region_code: &str) -> Result<(), RegionError> {
+    let reason = if region_code.len() != 6 {
+        "region_code's length must be 6"
+    } else if !region_code.bytes().all(|x| x.is_ascii_digit()) {
+        "region_code must be digits"
+    } else {
+        return Ok(());
+    };
+    Err(RegionError::InvalidCode {
+        input: region_code.to_string(),
+        reason: reason.to_string(),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{be_u8_slice_to_i32, AdminLevel, RegionLevel};
 
     #[test]
     fn test_region() {
-        let mut region = Region::new(PathBuf::from("data/region_full.dat"));
+        let region = Region::new(PathBuf::from("data/region_full.dat"));
         assert_eq!(region.get_version().unwrap(), "2024092911");
         let result = region.search_with_data("530925").unwrap();
         assert_eq!(result.name, "云南省临沧市双江拉祜族佤族布朗族傣族自治县");
@@ -315,4 +695,486 @@ mod tests {
         assert_eq!(result.name, "北京市崇文区");
         assert_eq!(result.discard_year, 2010);
     }
+
+    #[test]
+    fn test_open() {
+        assert!(matches!(
+            Region::open("data/not_exists.dat"),
+            Err(RegionError::IOError(_))
+        ));
+        let data = std::fs::read("data/region.dat").unwrap();
+        let dir = std::env::temp_dir();
+        // 截断的文件
+        let truncated = dir.join("region_cn_truncated.dat");
+        std::fs::write(&truncated, &data[..1000]).unwrap();
+        assert!(Region::open(&truncated).is_err());
+        // 只截断字符集的末尾，字符集仍然可以解码
+        for cut in [2, 4, 100] {
+            std::fs::write(&truncated, &data[..data.len() - cut]).unwrap();
+            assert!(
+                matches!(
+                    Region::open(&truncated),
+                    Err(RegionError::CorruptData { .. })
+                ),
+                "{cut}"
+            );
+            assert!(Region::from_bytes(data[..data.len() - cut].to_vec()).is_err());
+        }
+        // 不是数据文件
+        let not_region = dir.join("region_cn_not_region.dat");
+        std::fs::write(&not_region, "not a region data file").unwrap();
+        assert!(Region::open(&not_region).is_err());
+        let _ = std::fs::remove_file(truncated);
+        let _ = std::fs::remove_file(not_region);
+        assert!(Region::open("data/region.dat").is_ok());
+    }
+
+    #[test]
+    fn test_region_share_between_threads() {
+        let region = std::sync::Arc::new(Region::open("data/region_full.dat").unwrap());
+        let handles: Vec<_> = ["530925", "110103", "429004", "820000"]
+            .into_iter()
+            .map(|code| {
+                let region = region.clone();
+                std::thread::spawn(move || {
+                    let data = region.search_with_data(code).unwrap();
+                    let trie = region.search_with_trie(code).unwrap();
+                    assert_eq!(data.name, trie.name);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_search_by_name() {
+        let region = Region::new(PathBuf::from("data/region.dat"));
+        let result = region.search_by_name("临沧市双江县").unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].region_code, "530925");
+        assert_eq!(
+            result[0].region_slice,
+            vec!["云南省", "临沧市", "双江拉祜族佤族布朗族傣族自治县"]
+        );
+        let codes: Vec<String> = region
+            .search_by_name("朝阳区")
+            .unwrap()
+            .into_iter()
+            .map(|x| x.region_code)
+            .collect();
+        assert_eq!(codes, vec!["110105", "220104"]);
+        assert!(region.search_by_name("不存在的地方").is_err());
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let region = Region::new(PathBuf::from("data/region.dat"));
+        let provinces = region.provinces().unwrap();
+        assert_eq!(provinces.len(), 34);
+        assert_eq!(provinces[0].name, "北京市");
+        let children = region.children("530900").unwrap();
+        assert!(children.iter().any(|x| x.region_code == "530925"));
+        assert!(children.iter().all(|x| x.region_code.starts_with("5309")));
+        // 直辖市的区县
+        let children = region.children("110000").unwrap();
+        assert_eq!(children[0].region_slice, vec!["北京市", "东城区"]);
+        // 省直辖县级行政单位
+        let children = region.children("420000").unwrap();
+        assert!(children.iter().any(|x| x.region_code == "429004"));
+        let parent = region.parent("429004").unwrap().unwrap();
+        assert_eq!(parent.region_code, "420000");
+        assert!(region.parent("420000").unwrap().is_none());
+        let ancestors = region.ancestors("530925").unwrap();
+        assert_eq!(
+            ancestors
+                .iter()
+                .map(|x| x.region_code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["530000", "530900"]
+        );
+        assert_eq!(ancestors[1].name, "云南省临沧市");
+        let siblings = region.siblings("429004").unwrap();
+        assert!(siblings.iter().any(|x| x.region_code == "420100"));
+        assert!(siblings.iter().all(|x| x.region_code != "429004"));
+        assert_eq!(region.siblings("110000").unwrap().len(), 33);
+        assert!(region.children("530999").is_err());
+    }
+
+    #[test]
+    fn test_region_levels() {
+        let region = Region::new(PathBuf::from("data/region.dat"));
+        for result in [
+            region.search_with_data("530925").unwrap(),
+            region.search_with_trie("530925").unwrap(),
+        ] {
+            assert_eq!(
+                result.levels[1],
+                RegionLevel {
+                    code: "530900".to_string(),
+                    stem: "临沧".to_string(),
+                    region_type: RegionType::City,
+                    name: "临沧市".to_string(),
+                }
+            );
+            assert_eq!(result.levels[0].code, "530000");
+            assert_eq!(result.levels[0].region_type, RegionType::Province);
+            assert_eq!(result.levels[2].stem, "双江拉祜族佤族布朗族傣族");
+            assert_eq!(result.levels[2].region_type, RegionType::AutonomousCounty);
+            assert_eq!(result.levels[2].name, result.region_slice[2]);
+        }
+        let result = region.search_with_data("429004").unwrap();
+        assert_eq!(result.levels.len(), 2);
+        assert_eq!(result.levels[1].code, "429004");
+        // 特别行政区的简称
+        for result in [
+            region.search_with_data("810000").unwrap(),
+            region.search_with_trie("820000").unwrap(),
+        ] {
+            assert_eq!(result.levels[0].region_type, RegionType::Other);
+            assert!(["香港", "澳门"].contains(&result.levels[0].stem.as_str()));
+            assert!(result.levels[0].name.ends_with("特别行政区"));
+        }
+    }
+
+    #[test]
+    fn test_region_error() {
+        use std::error::Error;
+
+        let region = Region::new(PathBuf::from("data/region.dat"));
+        assert!(matches!(
+            region.search_with_data("5309"),
+            Err(RegionError::InvalidCode { .. })
+        ));
+        assert!(matches!(
+            region.search_with_trie("53092a"),
+            Err(RegionError::InvalidCode { .. })
+        ));
+        assert!(matches!(
+            region.search_with_data("990000"),
+            Err(RegionError::UnknownProvince(99))
+        ));
+        assert!(matches!(
+            region.children("530999"),
+            Err(RegionError::NotFound { .. })
+        ));
+        let err = Region::open("data/not_exists.dat").unwrap_err();
+        assert!(err.source().is_some());
+        let err = Region::open("data/region.py").unwrap_err();
+        assert!(matches!(err, RegionError::CorruptData { .. }));
+    }
+
+    #[test]
+    fn test_match_mode() {
+        let region = Region::new(PathBuf::from("data/region_full.dat"));
+        for result in [
+            region.search_with_data("530999").unwrap(),
+            region.search_with_trie("530999").unwrap(),
+        ] {
+            assert_eq!(result.name, "云南省临沧市");
+            assert_eq!(result.matched_code(), "530900");
+            assert_eq!(result.matched_level(), AdminLevel::Prefecture);
+            assert!(!result.is_exact());
+        }
+        assert!(matches!(
+            region.search_with_data_mode("530999", MatchMode::Strict),
+            Err(RegionError::NotFound { .. })
+        ));
+        assert!(matches!(
+            region.search_with_trie_mode("530999", MatchMode::Strict),
+            Err(RegionError::NotFound { .. })
+        ));
+        for code in ["530925", "429004", "110103", "110199"] {
+            let data = region.search_with_data(code).unwrap();
+            let trie = region.search_with_trie(code).unwrap();
+            assert_eq!(data.levels, trie.levels);
+            assert_eq!(data.discard_year, trie.discard_year);
+            assert_eq!(data.matched_code(), trie.matched_code());
+        }
+        let result = region
+            .search_with_trie_mode("429004", MatchMode::Strict)
+            .unwrap();
+        assert!(result.is_exact());
+        assert_eq!(result.matched_level(), AdminLevel::County);
+        // 两种搜索方式返回相同的错误
+        for (code, mode) in [
+            ("990000", MatchMode::Ancestor),
+            ("000000", MatchMode::Ancestor),
+            ("990101", MatchMode::Strict),
+            ("530999", MatchMode::Strict),
+            ("53092", MatchMode::Ancestor),
+            ("53092a", MatchMode::Strict),
+        ] {
+            let data = region.search_with_data_mode(code, mode).unwrap_err();
+            let trie = region.search_with_trie_mode(code, mode).unwrap_err();
+            assert_eq!(format!("{data:?}"), format!("{trie:?}"), "{code}");
+        }
+        assert!(matches!(
+            region.search_with_trie("990000"),
+            Err(RegionError::UnknownProvince(99))
+        ));
+    }
+
+    /// 每个省份的所有记录都能通过数据文件精确匹配
+    #[test]
+    fn test_search_every_province() {
+        let region = Region::new(PathBuf::from("data/region_full.dat"));
+        let records = region.get_record_from_data().unwrap();
+        assert_eq!(records.len(), 6415);
+        for record in records {
+            let result = region
+                .search_with_data_mode(&record.region_code, MatchMode::Strict)
+                .unwrap();
+            assert_eq!(result.levels.last(), record.levels.last());
+            assert_eq!(result.discard_year, record.discard_year);
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let report = Region::new(PathBuf::from("data/region_full.dat"))
+            .verify()
+            .unwrap();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.record_count, 6415);
+        assert_eq!(report.checksum_matched, None);
+        let data = std::fs::read("data/region.dat").unwrap();
+        // 第一条记录110000改成120000
+        let mut corrupted = data.clone();
+        let code_type = (120000 << 4) | (be_u8_slice_to_i32(&data[7..10]) & 0xF);
+        corrupted[7..10].copy_from_slice(&code_type.to_be_bytes()[1..]);
+        let report = Region::from_bytes(corrupted.clone())
+            .unwrap()
+            .verify()
+            .unwrap();
+        assert_eq!(
+            report.issues,
+            vec![
+                VerifyIssue::OutOfProvince {
+                    offset: 6,
+                    code: "120000".to_string()
+                },
+                VerifyIssue::Unsorted {
+                    offset: 6 + data[6] as u64,
+                    code: "110101".to_string(),
+                    previous: "120000".to_string()
+                },
+            ]
+        );
+        // 字符索引改成4095，打开时就会返回错误，通过`verify_bytes`校验
+        corrupted[10] = 0xFF;
+        corrupted[11] |= 0xF0;
+        assert!(matches!(
+            Region::from_bytes(corrupted.clone()),
+            Err(RegionError::CorruptData { offset: 6, .. })
+        ));
+        let report = Region::verify_bytes(&corrupted).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![
+                VerifyIssue::OutOfProvince {
+                    offset: 6,
+                    code: "120000".to_string()
+                },
+                VerifyIssue::UnknownChar {
+                    offset: 6,
+                    code: "120000".to_string(),
+                    index: 0xFFF
+                },
+                VerifyIssue::Unsorted {
+                    offset: 6 + data[6] as u64,
+                    code: "110101".to_string(),
+                    previous: "120000".to_string()
+                },
+            ]
+        );
+        // 记录大小为0
+        let mut corrupted = data.clone();
+        corrupted[6] = 0;
+        assert!(matches!(
+            Region::from_bytes(corrupted.clone()),
+            Err(RegionError::CorruptData { offset: 6, .. })
+        ));
+        let report = Region::verify_bytes(&corrupted).unwrap();
+        assert_eq!(report.record_count, 0);
+        assert!(matches!(
+            report.issues[..],
+            [VerifyIssue::RecordOutOfBounds { offset: 6, .. }]
+        ));
+        assert!(Region::verify_bytes(&data).unwrap().is_ok());
+        assert!(Region::verify_bytes(&data[..4]).is_err());
+    }
+
+    /// 随机修改和截断数据文件，所有解析和查询的方法都只能返回错误，不能panic
+    #[test]
+    fn test_corrupted_data_never_panics() {
+        let records: Vec<(String, String, u32)> = Region::open("data/region.dat")
+            .unwrap()
+            .get_record_from_data()
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.region_code, x.name, x.discard_year))
+            .collect();
+        let records = crate::writer::tests::as_refs(&records);
+        let mut samples = vec![std::fs::read("data/region.dat").unwrap()];
+        for format in [DataFormat::Indexed, DataFormat::V2] {
+            let writer = crate::writer::RegionWriter::new(2024092911).with_format(format);
+            samples.push(writer.pack(&records).unwrap());
+        }
+        // xorshift，结果是确定的
+        let mut seed: u64 = 2024092911;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for sample in &samples {
+            for i in 0..100 {
+                let mut data = sample.clone();
+                if i % 10 == 0 {
+                    data.truncate(next() as usize % data.len());
+                } else {
+                    // 文件头和索引附近更容易出问题
+                    for _ in 0..1 + next() % 4 {
+                        let offset = match next() % 3 {
+                            0 => next() as usize % 64,
+                            _ => next() as usize % data.len(),
+                        };
+                        data[offset] = next() as u8;
+                    }
+                }
+                let _ = Region::verify_bytes(&data);
+                let Ok(region) = Region::from_bytes(data) else {
+                    continue;
+                };
+                let _ = region.verify();
+                let _ = region.get_record_from_data();
+                for code in ["110000", "110101", "530925", "429004", "999999"] {
+                    let _ = region.search_with_data(code);
+                    let _ = region.search_as_of(code, 2000);
+                }
+                // 构建前缀树和名称索引比较慢
+                if i % 10 == 0 {
+                    let _ = region.search_with_trie("530925");
+                    let _ = region.children("110000");
+                    let _ = region.successors("110103");
+                    let _ = region.suggest_successors("110103");
+                    let _ = region.iter_valid_in(2000).map(|x| x.count());
+                }
+                if i % 50 == 0 {
+                    let _ = region.search_by_name("临沧市双江县");
+                    let _ = region.search_fuzzy("shuangjiang", 5);
+                    let _ = region.complete("临", &CompleteOptions::new(5));
+                    let _ = region.parse_address("云南临沧双江县勐勐镇");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_as_of() {
+        let region = Region::new(PathBuf::from("data/region_full.dat"));
+        let result = region.search_as_of("110103", 2009).unwrap();
+        assert_eq!(result.name, "北京市崇文区");
+        assert!(matches!(
+            region.search_as_of("110103", 2010),
+            Err(RegionError::NotValidInYear {
+                discard_year: 2010,
+                ..
+            })
+        ));
+        assert!(region.search_as_of("110101", 1980).is_ok());
+        assert!(region.search_as_of("110199", 1980).is_err());
+        // 最新数据中的地区在2024年都有效
+        let valid: Vec<RegionItem> = region.iter_valid_in(2024).unwrap().collect();
+        let valid_codes: Vec<&str> = valid.iter().map(|x| x.region_code.as_str()).collect();
+        for record in Region::new(PathBuf::from("data/region.dat"))
+            .get_record_from_data()
+            .unwrap()
+        {
+            assert!(valid_codes.contains(&record.region_code.as_str()));
+        }
+        assert!(valid.iter().all(|x| x.discard_year == 0));
+        assert_eq!(valid[0].name, "北京市");
+        let valid_1995: Vec<RegionItem> = region.iter_valid_in(1995).unwrap().collect();
+        assert!(valid_1995.iter().any(|x| x.name == "北京市崇文区"));
+        assert!(valid_1995
+            .iter()
+            .all(|x| x.discard_year == 0 || x.discard_year > 1995));
+    }
+
+    #[test]
+    fn test_suggest_successors() {
+        let region = Region::new(PathBuf::from("data/region_full.dat"));
+        // region_full.dat 中没有记录继承关系
+        assert!(region.successors("110103").unwrap().is_empty());
+        let result = region.suggest_successors("110103").unwrap();
+        assert_eq!(result[0].region_code, "110101");
+        assert_eq!(result[0].name, "北京市东城区");
+        assert!(result.iter().all(|x| x.discard_year == 0));
+        assert!(region.suggest_successors("110101").unwrap().is_empty());
+    }
+
+    #[cfg(feature = "embed-latest")]
+    #[test]
+    fn test_embedded_latest() {
+        let region = Region::embedded_latest();
+        assert_eq!(region.get_version().unwrap(), "2024092911");
+        assert_eq!(
+            region.search_with_data("530925").unwrap().name,
+            "云南省临沧市双江拉祜族佤族布朗族傣族自治县"
+        );
+        assert!(region
+            .search_with_data_mode("110103", MatchMode::Strict)
+            .is_err());
+    }
+
+    #[cfg(feature = "embed-full")]
+    #[test]
+    fn test_embedded_full() {
+        let region = Region::embedded_full();
+        let result = region.search_with_trie("110103").unwrap();
+        assert_eq!(result.name, "北京市崇文区");
+        assert_eq!(result.discard_year, 2010);
+    }
+
+    #[test]
+    fn test_region_from_bytes_and_reader() {
+        let data = std::fs::read("data/region_full.dat").unwrap();
+        let from_bytes = Region::from_bytes(data.clone()).unwrap();
+        let from_reader = Region::from_reader(std::io::Cursor::new(data)).unwrap();
+        for region in [from_bytes, from_reader] {
+            assert_eq!(region.get_version().unwrap(), "2024092911");
+            assert_eq!(
+                region.search_with_data("530925").unwrap().name,
+                "云南省临沧市双江拉祜族佤族布朗族傣族自治县"
+            );
+            assert_eq!(
+                region.search_with_trie("110103").unwrap().discard_year,
+                2010
+            );
+        }
+        assert!(matches!(
+            Region::from_bytes(&b"region"[..]),
+            Err(RegionError::CorruptData { .. })
+        ));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_region_mmap() {
+        let region = Region::open_mmap("data/region_full.dat").unwrap();
+        assert_eq!(region.get_version().unwrap(), "2024092911");
+        assert_eq!(
+            region.search_with_data("530925").unwrap().name,
+            "云南省临沧市双江拉祜族佤族布朗族傣族自治县"
+        );
+        assert_eq!(
+            region.search_with_trie("110103").unwrap().discard_year,
+            2010
+        );
+        assert!(Region::open_mmap("data/not_exists.dat").is_err());
+    }
 }
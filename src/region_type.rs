@@ -0,0 +1,225 @@
+//! 地区类型，对应数据文件中每条记录的4位类型
+
+/// 地区类型，和 data/region.py 中的`RegionType`一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RegionType {
+    /// 其他，比如特别行政区。数据文件中特别行政区按`区`记录，`RegionLevel`中会转换成这个类型，
+    /// 比如`810000`的简称是`香港`
+    #[default]
+    Other = 0,
+    /// 省
+    Province = 1,
+    /// 自治区
+    AutonomousRegion = 2,
+    /// 市
+    City = 3,
+    /// 区
+    District = 4,
+    /// 县
+    County = 5,
+    /// 自治县
+    AutonomousCounty = 6,
+    /// 旗
+    Banner = 7,
+    /// 盟
+    League = 8,
+    /// 州
+    Prefecture = 9,
+    /// 自治州
+    AutonomousPrefecture = 10,
+    /// 藏族自治州
+    TibetanAutonomousPrefecture = 11,
+    /// 满族自治县
+    ManchuAutonomousCounty = 12,
+    /// 蒙古族自治县
+    MongolAutonomousCounty = 13,
+    /// 苗族自治县
+    MiaoAutonomousCounty = 14,
+    /// 土家族自治县
+    TujiaAutonomousCounty = 15,
+}
+
+impl RegionType {
+    /// 通过数据文件中的类型值获取类型
+    pub fn from_id(id: i32) -> Option<Self> {
+        let region_type = match id {
+            0 => RegionType::Other,
+            1 => RegionType::Province,
+            2 => RegionType::AutonomousRegion,
+            3 => RegionType::City,
+            4 => RegionType::District,
+            5 => RegionType::County,
+            6 => RegionType::AutonomousCounty,
+            7 => RegionType::Banner,
+            8 => RegionType::League,
+            9 => RegionType::Prefecture,
+            10 => RegionType::AutonomousPrefecture,
+            11 => RegionType::TibetanAutonomousPrefecture,
+            12 => RegionType::ManchuAutonomousCounty,
+            13 => RegionType::MongolAutonomousCounty,
+            14 => RegionType::MiaoAutonomousCounty,
+            15 => RegionType::TujiaAutonomousCounty,
+            _ => return None,
+        };
+        Some(region_type)
+    }
+
+    /// 数据文件中的类型值
+    pub fn id(&self) -> i32 {
+        *self as i32
+    }
+
+    /// 英文名称
+    pub fn label_en(&self) -> &'static str {
+        match self {
+            RegionType::Other => "Other",
+            RegionType::Province => "Province",
+            RegionType::AutonomousRegion => "Autonomous Region",
+            RegionType::City => "City",
+            RegionType::District => "District",
+            RegionType::County => "County",
+            RegionType::AutonomousCounty => "Autonomous County",
+            RegionType::Banner => "Banner",
+            RegionType::League => "League",
+            RegionType::Prefecture => "Prefecture",
+            RegionType::AutonomousPrefecture => "Autonomous Prefecture",
+            RegionType::TibetanAutonomousPrefecture => "Tibetan Autonomous Prefecture",
+            RegionType::ManchuAutonomousCounty => "Manchu Autonomous County",
+            RegionType::MongolAutonomousCounty => "Mongol Autonomous County",
+            RegionType::MiaoAutonomousCounty => "Miao Autonomous County",
+            RegionType::TujiaAutonomousCounty => "Tujia Autonomous County",
+        }
+    }
+
+    /// 将名称分成不带后缀的名称和类型，和 data/region.py 中的`RegionType.name_classifiction`一致，
+    /// 比如`临沧市`分成`临沧`和`RegionType::City`，`香港特别行政区`分成`香港特别行政`和`RegionType::District`
+    pub fn classify(name: &str) -> (&str, RegionType) {
+        // 判断的顺序和 data/region.py 保持一致
+        const ORDER: [RegionType; 15] = [
+            RegionType::TujiaAutonomousCounty,
+            RegionType::MiaoAutonomousCounty,
+            RegionType::MongolAutonomousCounty,
+            RegionType::ManchuAutonomousCounty,
+            RegionType::TibetanAutonomousPrefecture,
+            RegionType::AutonomousRegion,
+            RegionType::AutonomousCounty,
+            RegionType::AutonomousPrefecture,
+            RegionType::Province,
+            RegionType::City,
+            RegionType::County,
+            RegionType::District,
+            RegionType::League,
+            RegionType::Prefecture,
+            RegionType::Banner,
+        ];
+        for region_type in ORDER {
+            if let Some(stem) = name.strip_suffix(region_type.suffix()) {
+                return (stem, region_type);
+            }
+        }
+        (name, RegionType::Other)
+    }
+
+    /// 名称的后缀
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            RegionType::Other => "",
+            RegionType::Province => "省",
+            RegionType::AutonomousRegion => "自治区",
+            RegionType::City => "市",
+            RegionType::District => "区",
+            RegionType::County => "县",
+            RegionType::AutonomousCounty => "自治县",
+            RegionType::Banner => "旗",
+            RegionType::League => "盟",
+            RegionType::Prefecture => "州",
+            RegionType::AutonomousPrefecture => "自治州",
+            RegionType::TibetanAutonomousPrefecture => "藏族自治州",
+            RegionType::ManchuAutonomousCounty => "满族自治县",
+            RegionType::MongolAutonomousCounty => "蒙古族自治县",
+            RegionType::MiaoAutonomousCounty => "苗族自治县",
+            RegionType::TujiaAutonomousCounty => "土家族自治县",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::*;
+
+    #[test]
+    fn test_region_type() {
+        for id in 0..16 {
+            assert_eq!(RegionType::from_id(id).unwrap().id(), id);
+        }
+        assert!(RegionType::from_id(16).is_none());
+        assert_eq!(RegionType::classify("临沧市"), ("临沧", RegionType::City));
+        assert_eq!(
+            RegionType::classify("双江拉祜族佤族布朗族傣族自治县"),
+            ("双江拉祜族佤族布朗族傣族", RegionType::AutonomousCounty)
+        );
+        assert_eq!(
+            RegionType::classify("彭水苗族土家族自治县"),
+            ("彭水苗族", RegionType::TujiaAutonomousCounty)
+        );
+        assert_eq!(
+            RegionType::classify("香港特别行政区"),
+            ("香港特别行政", RegionType::District)
+        );
+        assert_eq!(RegionType::classify("测试"), ("测试", RegionType::Other));
+        assert_eq!(RegionType::Banner.label_en(), "Banner");
+    }
+
+    /// 和 data/region.py 中的`RegionType`保持一致
+    #[test]
+    fn test_region_type_consistent_with_python() {
+        let script = fs::read_to_string("data/region.py").unwrap();
+        let lines: Vec<&str> = script.lines().map(|x| x.trim()).collect();
+        // province = 1  # 省
+        let mut ids = HashMap::new();
+        for line in lines.iter().take_while(|x| **x != "@property") {
+            if let Some((name, rest)) = line.split_once(" = ") {
+                if let Some(id) = rest.split_whitespace().next().and_then(|x| x.parse().ok()) {
+                    ids.insert(name.to_string(), id);
+                }
+            }
+        }
+        assert_eq!(ids.len(), 16);
+        let mut labels = 0;
+        let mut classifications = 0;
+        for (i, line) in lines.iter().enumerate() {
+            // elif self == RegionType.city:
+            //     return '市'
+            if let Some(name) = line
+                .strip_prefix("if self == RegionType.")
+                .or_else(|| line.strip_prefix("elif self == RegionType."))
+            {
+                let region_type = RegionType::from_id(ids[name.trim_end_matches(':')]).unwrap();
+                let label = lines[i + 1]
+                    .trim_start_matches("return ")
+                    .trim_matches('\'');
+                assert_eq!(region_type.suffix(), label, "label of {name}");
+                labels += 1;
+            }
+            // elif name.endswith('市'):
+            //     return name[:-1], RegionType.city
+            if let Some(suffix) = line
+                .strip_prefix("if name.endswith('")
+                .or_else(|| line.strip_prefix("elif name.endswith('"))
+            {
+                let suffix = suffix.trim_end_matches("'):");
+                let name = lines[i + 1].rsplit("RegionType.").next().unwrap();
+                let region_type = RegionType::from_id(ids[name]).unwrap();
+                assert_eq!(
+                    RegionType::classify(&format!("测试{suffix}")),
+                    ("测试", region_type)
+                );
+                classifications += 1;
+            }
+        }
+        assert_eq!(labels, 15);
+        assert_eq!(classifications, 15);
+    }
+}
@@ -0,0 +1,148 @@
+//! 数据来源，按偏移读取，多个线程可以同时读取
+use std::{
+    borrow::Cow,
+    fmt,
+    fs::File,
+    io::{self, Read, Seek},
+    sync::Mutex,
+};
+
+/// 可以读取和移动游标的数据
+pub(crate) trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+pub(crate) enum DataSource {
+    /// 数据文件
+    File { file: File, len: u64 },
+    /// 内存中的数据，包括编译到程序中的数据
+    Bytes(Cow<'static, [u8]>),
+    /// 映射到内存中的数据文件
+    #[cfg(feature = "mmap")]
+    Mmap(memmap2::Mmap),
+    /// 实现了`Read`和`Seek`的数据，读取时需要加锁
+    Reader {
+        reader: Mutex<Box<dyn ReadSeek>>,
+        len: u64,
+    },
+}
+
+impl fmt::Debug for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSource::File { file, len } => f
+                .debug_struct("File")
+                .field("file", file)
+                .field("len", len)
+                .finish(),
+            DataSource::Bytes(bytes) => f.debug_tuple("Bytes").field(&bytes.len()).finish(),
+            #[cfg(feature = "mmap")]
+            DataSource::Mmap(mmap) => f.debug_tuple("Mmap").field(&mmap.len()).finish(),
+            DataSource::Reader { len, .. } => f.debug_struct("Reader").field("len", len).finish(),
+        }
+    }
+}
+
+impl DataSource {
+    pub(crate) fn from_file(file: File) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+        Ok(DataSource::File { file, len })
+    }
+
+    pub(crate) fn from_reader<R: Read + Seek + Send + 'static>(mut reader: R) -> io::Result<Self> {
+        let len = reader.seek(io::SeekFrom::End(0))?;
+        Ok(DataSource::Reader {
+            reader: Mutex::new(Box::new(reader)),
+            len,
+        })
+    }
+
+    /// 数据长度
+    pub(crate) fn len(&self) -> u64 {
+        match self {
+            DataSource::File { len, .. } => *len,
+            DataSource::Bytes(bytes) => bytes.len() as u64,
+            #[cfg(feature = "mmap")]
+            DataSource::Mmap(mmap) => mmap.len() as u64,
+            DataSource::Reader { len, .. } => *len,
+        }
+    }
+
+    /// 从指定偏移读满`buf`，不改变文件游标
+    pub(crate) fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
+        match self {
+            DataSource::File { file, .. } => read_file_at(file, buf, offset),
+            DataSource::Bytes(bytes) => {
+                buf.copy_from_slice(slice_at(bytes, offset, buf.len())?);
+                Ok(())
+            }
+            #[cfg(feature = "mmap")]
+            DataSource::Mmap(mmap) => {
+                buf.copy_from_slice(slice_at(mmap, offset, buf.len())?);
+                Ok(())
+            }
+            DataSource::Reader { reader, .. } => {
+                let mut reader = reader
+                    .lock()
+                    .map_err(|_| io::Error::other("reader lock poisoned"))?;
+                reader.seek(io::SeekFrom::Start(offset))?;
+                reader.read_exact(buf)
+            }
+        }
+    }
+
+    /// 读取`offset`开始的`len`个字节，内存中的数据直接返回切片，不会复制
+    pub(crate) fn read_range(&self, offset: u64, len: usize) -> io::Result<Cow<'_, [u8]>> {
+        match self {
+            DataSource::Bytes(bytes) => Ok(Cow::Borrowed(slice_at(bytes, offset, len)?)),
+            #[cfg(feature = "mmap")]
+            DataSource::Mmap(mmap) => Ok(Cow::Borrowed(slice_at(mmap, offset, len)?)),
+            _ => {
+                let mut buf = vec![0u8; len];
+                self.read_at(&mut buf, offset)?;
+                Ok(Cow::Owned(buf))
+            }
+        }
+    }
+}
+
+/// `offset`开始的`len`个字节，超出范围时返回`UnexpectedEof`
+fn slice_at(bytes: &[u8], offset: u64, len: usize) -> io::Result<&[u8]> {
+    let start = usize::try_from(offset).unwrap_or(usize::MAX);
+    start
+        .checked_add(len)
+        .and_then(|end| bytes.get(start..end))
+        .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+}
+
+fn read_file_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileExt;
+        file.read_exact_at(buf, offset)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::FileExt;
+        let mut buf = buf;
+        let mut offset = offset;
+        while !buf.is_empty() {
+            match file.seek_read(buf, offset) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => {
+                    buf = &mut buf[n..];
+                    offset += n as u64;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let mut file = file;
+        file.seek(io::SeekFrom::Start(offset))?;
+        file.read_exact(buf)
+    }
+}
@@ -1,27 +1,52 @@
 //! 前缀树实现，每个节点代表2位地区代码
 use std::collections::HashMap;
 
-use crate::{RegionError, RegionItem};
+use crate::{MatchMode, RegionError, RegionItem, RegionLevel, RegionType};
 
 #[derive(Debug, Clone, Default)]
 pub struct RegionNameItem {
     text: String,
     discard_year: u32,
+    established_year: u32,
+    stem: String,
+    region_type: RegionType,
 }
 
 #[derive(Debug, Clone)]
 pub struct RegionNode {
     children: HashMap<String, RegionNode>,
     item: RegionNameItem,
+    // 补齐到6位的地区码
+    code: String,
 }
 
 impl RegionNode {
-    fn new(item: RegionNameItem) -> Self {
+    fn new(item: RegionNameItem, code: String) -> Self {
         RegionNode {
             children: HashMap::new(),
             item,
+            code,
         }
     }
+
+    /// 是否有对应的记录，省直辖县级行政单位和直辖市的区县没有地级的记录，比如`429004`仙桃市
+    fn is_region(&self) -> bool {
+        !self.item.text.is_empty()
+    }
+
+    /// 下一级地区，会跳过没有记录的中间节点
+    fn region_children(&self) -> Vec<&RegionNode> {
+        let mut res = Vec::new();
+        for child in self.children.values() {
+            if child.is_region() {
+                res.push(child);
+            } else {
+                res.extend(child.region_children());
+            }
+        }
+        res.sort_by(|a, b| a.code.cmp(&b.code));
+        res
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,12 +57,20 @@ pub struct RegionTrie {
 impl RegionTrie {
     pub fn new() -> Self {
         RegionTrie {
-            root: RegionNode::new(RegionNameItem::default()),
+            root: RegionNode::new(RegionNameItem::default(), String::new()),
         }
     }
 
-    /// 插入地区码和地区
+    /// 插入地区码和地区，类型通过名称的后缀判断
     pub fn insert(&mut self, key: String, value: String, discard_year: u32) {
+        let (stem, region_type) = RegionType::classify(&value);
+        let level = RegionLevel::new(key, stem.to_string(), region_type);
+        self.insert_level(level, discard_year, 0);
+    }
+
+    /// 插入一级地区，保留名称和类型，设立年份为0表示未知
+    pub fn insert_level(&mut self, level: RegionLevel, discard_year: u32, established_year: u32) {
+        let key = level.code;
         let mut node = &mut self.root;
         let trimed_key = key.trim_end_matches("00");
         for (i, s) in trimed_key
@@ -47,26 +80,115 @@ impl RegionTrie {
             .map(|chunk| chunk.iter().collect::<String>())
             .enumerate()
         {
-            node = node.children.entry(s).or_insert_with(|| {
-                let mut text = String::new();
-                if i + 1 == trimed_key.len() / 2 {
-                    text = value.clone();
-                    RegionNode::new(RegionNameItem { text, discard_year })
-                } else {
-                    RegionNode::new(RegionNameItem {
-                        text,
-                        discard_year: 0,
-                    })
-                }
-            });
+            let code = format!("{:0<6}", &trimed_key[..(i + 1) * 2]);
+            node = node
+                .children
+                .entry(s)
+                .or_insert_with(|| RegionNode::new(RegionNameItem::default(), code));
+            if i + 1 == trimed_key.len() / 2 {
+                node.item = RegionNameItem {
+                    text: level.name.clone(),
+                    discard_year,
+                    established_year,
+                    stem: level.stem.clone(),
+                    region_type: level.region_type,
+                };
+            }
         }
     }
 
     // 搜索地区码
     pub fn search(&self, region_code: &str) -> Result<RegionItem, RegionError> {
+        self.search_with_mode(region_code, MatchMode::Ancestor)
+    }
+
+    /// 按指定的匹配方式搜索地区码
+    pub fn search_with_mode(
+        &self,
+        region_code: &str,
+        mode: MatchMode,
+    ) -> Result<RegionItem, RegionError> {
+        let path = self.path(region_code);
+        // 和数据文件搜索一致，没有这个省份时返回`UnknownProvince`
+        if path.is_empty() {
+            if let Some(code) = region_code.get(..2).and_then(|x| x.parse().ok()) {
+                return Err(RegionError::UnknownProvince(code));
+            }
+        }
+        let mut item = Self::path_item(&path);
+        item.region_code = region_code.to_string();
+        if item.levels.is_empty() || (mode == MatchMode::Strict && !item.is_exact()) {
+            return Err(RegionError::NotFound {
+                code: region_code.to_string(),
+            });
+        }
+        Ok(item)
+    }
+
+    /// 所有的省级地区
+    pub fn provinces(&self) -> Vec<RegionItem> {
+        self.root
+            .region_children()
+            .into_iter()
+            .map(|x| Self::path_item(&self.path(&x.code)))
+            .collect()
+    }
+
+    /// 下一级地区，省直辖的县级地区作为省的下一级
+    pub fn children(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        let node = self.region_node(region_code)?;
+        Ok(node
+            .region_children()
+            .into_iter()
+            .map(|x| Self::path_item(&self.path(&x.code)))
+            .collect())
+    }
+
+    /// 上一级地区，省级地区返回`None`
+    pub fn parent(&self, region_code: &str) -> Result<Option<RegionItem>, RegionError> {
+        Ok(self.ancestors(region_code)?.pop())
+    }
+
+    /// 所有上级地区，省级在前
+    pub fn ancestors(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        self.region_node(region_code)?;
+        let path = self.path(region_code);
+        Ok((1..path.len())
+            .filter(|i| path[i - 1].is_region())
+            .map(|i| Self::path_item(&path[..i]))
+            .collect())
+    }
+
+    /// 同一上级下的其他地区
+    pub fn siblings(&self, region_code: &str) -> Result<Vec<RegionItem>, RegionError> {
+        let node = self.region_node(region_code)?;
+        let items = match self.parent(region_code)? {
+            Some(parent) => self.children(&parent.region_code)?,
+            None => self.provinces(),
+        };
+        Ok(items
+            .into_iter()
+            .filter(|x| x.region_code != node.code)
+            .collect())
+    }
+
+    /// 查找有记录的节点
+    fn region_node(&self, region_code: &str) -> Result<&RegionNode, RegionError> {
+        let code = format!("{:0<6}", region_code.trim_end_matches("00"));
+        match self.path(region_code).pop() {
+            Some(node) if node.is_region() && node.code == code => Ok(node),
+            _ => Err(RegionError::NotFound {
+                code: region_code.to_string(),
+            }),
+        }
+    }
+
+    /// 从根节点到地区码的路径，遇到不存在的节点时停止
+    fn path(&self, region_code: &str) -> Vec<&RegionNode> {
         let mut node = &self.root;
-        let mut res: Vec<RegionNameItem> = Vec::new();
+        let mut res = Vec::new();
         for s in region_code
+            .trim_end_matches("00")
             .chars()
             .collect::<Vec<_>>()
             .chunks(2)
@@ -75,23 +197,40 @@ impl RegionTrie {
             match node.children.get(&s) {
                 Some(next_node) => {
                     node = next_node;
-                    res.push(next_node.item.clone());
+                    res.push(next_node);
                 }
                 None => {
                     break;
                 }
             }
         }
-        if res.is_empty() {
-            return Err(RegionError::Message("cannot find record".to_string()));
-        }
-        let region_slice: Vec<String> = res.iter().map(|x| x.text.clone()).collect();
-        Ok(RegionItem {
-            region_code: region_code.to_string(),
+        res
+    }
+
+    /// 根据路径生成结果，跳过没有记录的中间节点，废止年份和设立年份是最后一个有记录的节点的
+    fn path_item(path: &[&RegionNode]) -> RegionItem {
+        let levels: Vec<RegionLevel> = path
+            .iter()
+            .filter(|x| x.is_region())
+            .map(|x| RegionLevel {
+                code: x.code.clone(),
+                stem: x.item.stem.clone(),
+                region_type: x.item.region_type,
+                name: x.item.text.clone(),
+            })
+            .collect();
+        let region_slice: Vec<String> = levels.iter().map(|x| x.name.clone()).collect();
+        let last = path.iter().rfind(|x| x.is_region());
+        let discard_year = last.map(|x| x.item.discard_year).unwrap_or_default();
+        let established_year = last.map(|x| x.item.established_year).unwrap_or_default();
+        RegionItem {
+            region_code: path.last().map(|x| x.code.clone()).unwrap_or_default(),
             name: region_slice.join(""),
             region_slice,
-            discard_year: res.last().unwrap().discard_year,
-        })
+            discard_year,
+            established_year,
+            levels,
+        }
     }
 }
 
@@ -137,4 +276,30 @@ mod tests {
             String::from("五指山市")
         );
     }
+
+    #[test]
+    fn test_region_trie_hierarchy() {
+        let mut tree = RegionTrie::new();
+        tree.insert(String::from("420000"), String::from("湖北省"), 0);
+        tree.insert(String::from("420100"), String::from("武汉市"), 0);
+        tree.insert(String::from("420102"), String::from("江岸区"), 0);
+        tree.insert(String::from("429004"), String::from("仙桃市"), 0);
+        let result = tree.search("429004").unwrap();
+        assert_eq!(result.region_slice, vec!["湖北省", "仙桃市"]);
+        let children = tree.children("420000").unwrap();
+        assert_eq!(
+            children
+                .iter()
+                .map(|x| x.region_code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["420100", "429004"]
+        );
+        assert_eq!(
+            tree.parent("429004").unwrap().unwrap().region_code,
+            "420000"
+        );
+        assert_eq!(tree.ancestors("420102").unwrap().len(), 2);
+        assert_eq!(tree.siblings("420100").unwrap()[0].name, "湖北省仙桃市");
+        assert!(tree.children("429000").is_err());
+    }
 }
@@ -0,0 +1,273 @@
+//! 解析和校验统一社会信用代码(GB 32100)，第3到8位的登记管理机关行政区划码通过`Region`查询地区
+use std::fmt;
+
+use crate::{
+    region::{search_with_fallback, Region},
+    RegionError, RegionItem,
+};
+
+// 代码字符集，不使用I、O、Z、S、V
+const CHARSET: &str = "0123456789ABCDEFGHJKLMNPQRTUWXY";
+
+// 前17位的权重
+const WEIGHTS: [u32; 17] = [
+    1, 3, 9, 27, 19, 26, 16, 17, 20, 29, 25, 13, 8, 24, 10, 30, 28,
+];
+
+/// 登记管理部门，代码的第1位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationAuthority {
+    /// 机构编制，`1`
+    Institutional,
+    /// 外交，`2`
+    ForeignAffairs,
+    /// 司法行政，`3`
+    Justice,
+    /// 文化，`4`
+    Culture,
+    /// 民政，`5`
+    CivilAffairs,
+    /// 旅游，`6`
+    Tourism,
+    /// 宗教，`7`
+    Religion,
+    /// 工会，`8`
+    TradeUnion,
+    /// 工商(市场监管)，`9`
+    Industry,
+    /// 中央军委改革和编制办公室，`A`
+    Military,
+    /// 农业，`N`
+    Agriculture,
+    /// 其他，`Y`
+    Other,
+}
+
+impl RegistrationAuthority {
+    /// 通过代码的第1位获取登记管理部门
+    pub fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '1' => Self::Institutional,
+            '2' => Self::ForeignAffairs,
+            '3' => Self::Justice,
+            '4' => Self::Culture,
+            '5' => Self::CivilAffairs,
+            '6' => Self::Tourism,
+            '7' => Self::Religion,
+            '8' => Self::TradeUnion,
+            '9' => Self::Industry,
+            'A' => Self::Military,
+            'N' => Self::Agriculture,
+            'Y' => Self::Other,
+            _ => return None,
+        })
+    }
+
+    /// 中文名称，比如`工商`
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Institutional => "机构编制",
+            Self::ForeignAffairs => "外交",
+            Self::Justice => "司法行政",
+            Self::Culture => "文化",
+            Self::CivilAffairs => "民政",
+            Self::Tourism => "旅游",
+            Self::Religion => "宗教",
+            Self::TradeUnion => "工会",
+            Self::Industry => "工商",
+            Self::Military => "中央军委改革和编制办公室",
+            Self::Agriculture => "农业",
+            Self::Other => "其他",
+        }
+    }
+
+    /// 机构类别的中文名称，代码的第2位，类别不存在时返回`None`
+    pub fn entity_type_label(&self, c: char) -> Option<&'static str> {
+        let label = match (self, c) {
+            (Self::Institutional, '1') => "机关",
+            (Self::Institutional, '2') => "事业单位",
+            (Self::Institutional, '3') => "中央编办直接管理机构编制的群众团体",
+            (Self::ForeignAffairs, '1') => "外国常驻新闻机构",
+            (Self::Justice, '1') => "律师执业机构",
+            (Self::Justice, '2') => "公证处",
+            (Self::Justice, '3') => "基层法律服务所",
+            (Self::Justice, '4') => "司法鉴定机构",
+            (Self::Justice, '5') => "仲裁委员会",
+            (Self::Culture, '1') => "外国在华文化中心",
+            (Self::CivilAffairs, '1') => "社会团体",
+            (Self::CivilAffairs, '2') => "民办非企业单位",
+            (Self::CivilAffairs, '3') => "基金会",
+            (Self::Tourism, '1') => "外国旅游部门常驻代表机构",
+            (Self::Tourism, '2') => "港澳台地区旅游部门常驻内地(大陆)代表机构",
+            (Self::Religion, '1') => "宗教活动场所",
+            (Self::Religion, '2') => "宗教院校",
+            (Self::TradeUnion, '1') => "基层工会",
+            (Self::Industry, '1') => "企业",
+            (Self::Industry, '2') => "个体工商户",
+            (Self::Industry, '3') => "农民专业合作社",
+            (Self::Military, '1') => "军队事业单位",
+            (Self::Agriculture, '1') => "组级集体经济组织",
+            (Self::Agriculture, '2') => "村级集体经济组织",
+            (Self::Agriculture, '3') => "乡镇级集体经济组织",
+            (Self::Other, '1') => "其他",
+            // 除了工商和其他，类别9都是其他
+            (Self::Industry | Self::Other, _) => return None,
+            (_, '9') => "其他",
+            _ => return None,
+        };
+        Some(label)
+    }
+}
+
+/// 统一社会信用代码存在的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsccIssue {
+    /// 校验码不正确
+    ChecksumMismatch {
+        /// 根据前17位计算的校验码
+        expected: char,
+        /// 代码中的校验码
+        actual: char,
+    },
+    /// 登记管理部门不存在
+    UnknownAuthority(char),
+    /// 机构类别不存在
+    UnknownEntityType(char),
+    /// 最新的数据和包含废止地区码的数据中都没有第3到8位对应的地区
+    UnknownRegion(String),
+}
+
+impl fmt::Display for UsccIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UsccIssue::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "check character mismatch: expected {}, actual {}",
+                expected, actual
+            ),
+            UsccIssue::UnknownAuthority(c) => write!(f, "unknown registration authority: {}", c),
+            UsccIssue::UnknownEntityType(c) => write!(f, "unknown entity type: {}", c),
+            UsccIssue::UnknownRegion(code) => write!(f, "unknown region code: {}", code),
+        }
+    }
+}
+
+/// 统一社会信用代码的解析结果
+#[derive(Debug, Clone)]
+pub struct Uscc {
+    /// 18位代码，字母都是大写的
+    pub code: String,
+    /// 登记管理部门，不存在时为`None`
+    pub registration_authority: Option<RegistrationAuthority>,
+    /// 机构类别，代码的第2位
+    pub entity_type: char,
+    /// 机构类别的中文名称，不存在时为`None`
+    pub entity_type_label: Option<&'static str>,
+    /// 第3到8位对应的地区，没有找到时为`None`
+    pub region: Option<RegionItem>,
+    /// 主体标识码(组织机构代码)，第9到17位
+    pub organization_code: String,
+    /// 发现的问题，为空表示代码有效
+    pub issues: Vec<UsccIssue>,
+}
+
+impl Uscc {
+    /// 解析统一社会信用代码，`region`是最新的数据，`full`是包含废止地区码的数据，
+    /// 第3到8位在`region`中不存在时再从`full`中查询。
+    /// 长度或字符不正确时返回`RegionError::InvalidCode`，其他问题记录在`issues`中
+    pub fn parse(code: &str, region: &Region, full: Option<&Region>) -> Result<Self, RegionError> {
+        let code = code.trim().to_ascii_uppercase();
+        if code.chars().count() != 18 || !code.chars().all(|c| CHARSET.contains(c)) {
+            return Err(RegionError::InvalidCode {
+                input: code,
+                reason: "must be 18 characters of 0-9 and A-Y except I, O, S, V and Z".to_string(),
+            });
+        }
+        let chars: Vec<char> = code.chars().collect();
+        let mut issues = Vec::new();
+        let registration_authority = RegistrationAuthority::from_char(chars[0]);
+        let entity_type = chars[1];
+        let entity_type_label = match registration_authority {
+            Some(authority) => authority.entity_type_label(entity_type),
+            None => {
+                issues.push(UsccIssue::UnknownAuthority(chars[0]));
+                None
+            }
+        };
+        if registration_authority.is_some() && entity_type_label.is_none() {
+            issues.push(UsccIssue::UnknownEntityType(entity_type));
+        }
+        let region_code = &code[2..8];
+        let region = search_with_fallback(region, full, region_code)?;
+        if region.is_none() {
+            issues.push(UsccIssue::UnknownRegion(region_code.to_string()));
+        }
+        let expected = check_char(&code[..17]);
+        if expected != chars[17] {
+            issues.push(UsccIssue::ChecksumMismatch {
+                expected,
+                actual: chars[17],
+            });
+        }
+        Ok(Self {
+            organization_code: code[8..17].to_string(),
+            code,
+            registration_authority,
+            entity_type,
+            entity_type_label,
+            region,
+            issues,
+        })
+    }
+
+    /// 是否没有发现问题
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// 根据前17位计算校验码：加权和除以31的余数，用31减去后对应的字符，31对应`0`
+fn check_char(body: &str) -> char {
+    let sum: u32 = body
+        .chars()
+        .filter_map(|c| CHARSET.find(c))
+        .zip(WEIGHTS)
+        .map(|(i, w)| i as u32 * w)
+        .sum();
+    let index = (31 - sum % 31) % 31;
+    CHARSET.as_bytes()[index as usize] as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uscc() {
+        let region = Region::open("data/region.dat").unwrap();
+        let full = Region::open("data/region_full.dat").unwrap();
+        let uscc = Uscc::parse("91350100M000100Y43", &region, None).unwrap();
+        assert!(uscc.is_valid());
+        assert_eq!(
+            uscc.registration_authority,
+            Some(RegistrationAuthority::Industry)
+        );
+        assert_eq!(uscc.entity_type_label, Some("企业"));
+        assert_eq!(uscc.region.unwrap().name, "福建省福州市");
+        assert_eq!(uscc.organization_code, "M000100Y4");
+        // 废止的地区码需要从包含废止地区码的数据中查询
+        let uscc = Uscc::parse("52110103mj0000000k", &region, None).unwrap();
+        assert_eq!(
+            uscc.issues,
+            vec![UsccIssue::UnknownRegion("110103".to_string())]
+        );
+        let uscc = Uscc::parse("52110103MJ0000000K", &region, Some(&full)).unwrap();
+        assert!(uscc.is_valid());
+        assert_eq!(uscc.entity_type_label, Some("民办非企业单位"));
+        assert_eq!(uscc.region.unwrap().name, "北京市崇文区");
+        let uscc = Uscc::parse("B4110103MJ0000000Y", &region, Some(&full)).unwrap();
+        assert_eq!(uscc.issues.len(), 2);
+        assert!(Uscc::parse("91350100M000100Y4", &region, None).is_err());
+        assert!(Uscc::parse("91350100I000100Y43", &region, None).is_err());
+    }
+}
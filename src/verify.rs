@@ -0,0 +1,150 @@
+//! 校验数据文件，结果通过`Region::verify`或者`Region::verify_bytes`获取
+use std::fmt;
+
+use crate::DataFormat;
+
+/// 校验发现的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyIssue {
+    /// 文件头中的校验和和数据不匹配
+    ChecksumMismatch {
+        /// 文件头中的校验和
+        expected: u32,
+        /// 根据数据计算的校验和
+        actual: u32,
+    },
+    /// 记录的大小不正确或者超出记录区，之后的记录无法解析，只有`Region::verify_bytes`会返回
+    RecordOutOfBounds {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 详细信息
+        detail: String,
+    },
+    /// 地区码不是6位
+    InvalidCode {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 地区码
+        code: String,
+    },
+    /// 字符索引不在字符集中，只有`Region::verify_bytes`会返回
+    UnknownChar {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 地区码
+        code: String,
+        /// 字符索引
+        index: u32,
+    },
+    /// 类型不存在，`DataFormat::V2`的类型字段虽然有1个字节，也只能是`RegionType`中的类型
+    UnknownType {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 地区码
+        code: String,
+        /// 类型值
+        region_type: i32,
+    },
+    /// 地区码没有按从小到大的顺序排列
+    Unsorted {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 地区码
+        code: String,
+        /// 上一条记录的地区码
+        previous: String,
+    },
+    /// 记录不在所属省份的范围内
+    OutOfProvince {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 地区码
+        code: String,
+    },
+    /// 记录不在4位地区码索引的范围内
+    OutOfCity {
+        /// 记录在数据文件中的偏移
+        offset: u64,
+        /// 地区码
+        code: String,
+    },
+}
+
+impl fmt::Display for VerifyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyIssue::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected {:08x}, actual {:08x}",
+                expected, actual
+            ),
+            VerifyIssue::RecordOutOfBounds { offset, detail } => {
+                write!(
+                    f,
+                    "record at offset {} is out of bounds: {}",
+                    offset, detail
+                )
+            }
+            VerifyIssue::InvalidCode { offset, code } => {
+                write!(f, "invalid region code {} at offset {}", code, offset)
+            }
+            VerifyIssue::UnknownChar {
+                offset,
+                code,
+                index,
+            } => write!(
+                f,
+                "char index {} of {} at offset {} is not in the char table",
+                index, code, offset
+            ),
+            VerifyIssue::UnknownType {
+                offset,
+                code,
+                region_type,
+            } => write!(
+                f,
+                "unknown region type {} of {} at offset {}",
+                region_type, code, offset
+            ),
+            VerifyIssue::Unsorted {
+                offset,
+                code,
+                previous,
+            } => write!(
+                f,
+                "region code {} at offset {} is not greater than {}",
+                code, offset, previous
+            ),
+            VerifyIssue::OutOfProvince { offset, code } => write!(
+                f,
+                "region code {} at offset {} is outside its province block",
+                code, offset
+            ),
+            VerifyIssue::OutOfCity { offset, code } => write!(
+                f,
+                "region code {} at offset {} is outside its city block",
+                code, offset
+            ),
+        }
+    }
+}
+
+/// 数据文件的校验结果
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    /// 数据文件的格式
+    pub format: DataFormat,
+    /// 解析的记录数
+    pub record_count: usize,
+    /// 校验和是否匹配，数据文件中没有校验和时为`None`
+    pub checksum_matched: Option<bool>,
+    /// 发现的问题，按在数据文件中的顺序排列
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// 是否没有发现问题
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -0,0 +1,715 @@
+//! 生成 region.dat 数据文件，和 data/region.py 中的`RegionCtr.pack`格式一致
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+};
+
+use encoding::{all::GBK, EncoderTrap, Encoding};
+
+use crate::{
+    format::{
+        crc32, FLAG_CHECKSUM, FLAG_SORTED_CHARS, MAGIC, MAX_INDEX_OFFSET, RECORD_FLAG_DISCARD_YEAR,
+        RECORD_FLAG_ESTABLISHED_YEAR, SECTION_COUNT, SECTION_COUNT_WITH_SUCCESSORS,
+    },
+    region::PROVINCE_CODES,
+    CharOrder, DataFormat, RegionError, RegionType, SORTED_CHARS_FLAG,
+};
+
+/// 数据文件生成器
+#[derive(Debug, Clone)]
+pub struct RegionWriter {
+    version: u32,
+    char_table: Option<Vec<char>>,
+    char_order: CharOrder,
+    format: DataFormat,
+    established_years: HashMap<String, u32>,
+    successors: BTreeMap<String, Vec<String>>,
+}
+
+impl RegionWriter {
+    /// `version`是写入文件头的版本号，比如`2024092911`
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            char_table: None,
+            char_order: CharOrder::Unspecified,
+            format: DataFormat::Legacy,
+            established_years: HashMap::new(),
+            successors: BTreeMap::new(),
+        }
+    }
+
+    /// 指定字符集的顺序，不指定时按字符在名称中第一次出现的顺序排列。
+    /// 使用已有数据文件的`Region::char_table`可以生成完全一致的文件
+    pub fn with_char_table(mut self, char_table: Vec<char>) -> Self {
+        self.char_table = Some(char_table);
+        self
+    }
+
+    /// 指定字符集的排序规则，`CharOrder::Gbk`会按GBK编码排序并记录在文件头中，
+    /// 相同的数据总是生成相同的文件。会忽略`with_char_table`指定的顺序
+    pub fn with_char_order(mut self, char_order: CharOrder) -> Self {
+        self.char_order = char_order;
+        self
+    }
+
+    /// 指定数据文件的格式，默认是`DataFormat::Legacy`，和 data/region.py 生成的一致
+    pub fn with_format(mut self, format: DataFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 指定地区码的设立年份，只有`DataFormat::V2`可以记录，其他格式打包时会返回错误
+    pub fn with_established_years(mut self, established_years: HashMap<String, u32>) -> Self {
+        self.established_years = established_years;
+        self
+    }
+
+    /// 指定废止地区码由哪些地区继承，比如`110103`由`110101`继承，地区码都需要在记录中，
+    /// 被继承的地区码需要已经废止。只有带文件头的格式可以记录，`DataFormat::Legacy`打包时会返回错误
+    pub fn with_successors(mut self, successors: HashMap<String, Vec<String>>) -> Self {
+        self.successors = successors.into_iter().collect();
+        self
+    }
+
+    /// 将`(地区码, 名称, 废止年份)`打包，废止年份为0表示未废止，名称中的`*`会被去掉。
+    /// 记录需要按地区码排序，`DataFormat::V2`之前的格式需要包含所有省份。
+    /// 所有格式的地区码都需要是6位
+    pub fn pack(&self, records: &[(&str, &str, u32)]) -> Result<Vec<u8>, RegionError> {
+        let mut names = Vec::new();
+        for (code, name, _) in records {
+            if code.len() != 6 || !code.bytes().all(|x| x.is_ascii_digit()) {
+                return Err(RegionError::InvalidCode {
+                    input: code.to_string(),
+                    reason: "region code must be 6 digits".to_string(),
+                });
+            }
+            let name = name.replace('*', "");
+            let (stem, region_type) = RegionType::classify(&name);
+            names.push((stem.to_string(), region_type));
+        }
+        let char_list = match &self.char_table {
+            Some(char_table) if self.char_order == CharOrder::Unspecified => char_table.clone(),
+            _ => {
+                let mut char_list = Vec::new();
+                for (stem, _) in &names {
+                    for c in stem.chars() {
+                        if !char_list.contains(&c) {
+                            char_list.push(c);
+                        }
+                    }
+                }
+                if self.char_order == CharOrder::Gbk {
+                    char_list.sort_by_cached_key(|c| {
+                        GBK.encode(&c.to_string(), EncoderTrap::Strict)
+                            .unwrap_or_default()
+                    });
+                }
+                char_list
+            }
+        };
+        let wide = self.format == DataFormat::V2;
+        if !wide && !self.established_years.is_empty() {
+            return Err(RegionError::Message(
+                "established years require DataFormat::V2".to_string(),
+            ));
+        }
+        if self.format == DataFormat::Legacy && !self.successors.is_empty() {
+            return Err(RegionError::Message(
+                "successors require a headered format".to_string(),
+            ));
+        }
+        // v2之前是12位，前64个值保留给废止年份；v2是16位
+        let (char_base, max_chars) = if wide {
+            (0, 0x10000)
+        } else {
+            (64, 0xFFF - 64 + 1)
+        };
+        if char_list.len() > max_chars {
+            return Err(RegionError::Message("too many chars".to_string()));
+        }
+        let char_map: HashMap<char, u32> = char_list
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, i as u32 + char_base))
+            .collect();
+        if self.version == 0 || self.version & SORTED_CHARS_FLAG != 0 {
+            return Err(RegionError::Message(format!(
+                "invalid version: {}",
+                self.version
+            )));
+        }
+        // 记录区，偏移是相对记录区开头的
+        let mut data = Vec::new();
+        let mut offset_map: Vec<(i32, usize)> = Vec::new();
+        let mut city_offset_map: Vec<(i32, usize)> = Vec::new();
+        let mut last_code = "";
+        for ((code, _, discard_year), (stem, region_type)) in records.iter().zip(names) {
+            if *code <= last_code {
+                return Err(RegionError::Message(format!(
+                    "region code {code} is not in ascending order"
+                )));
+            }
+            last_code = code;
+            let code_2: i32 = code[..2].parse()?;
+            if offset_map.last().map(|x| x.0) != Some(code_2) {
+                offset_map.push((code_2, data.len()));
+            }
+            let code_4: i32 = code[..4].parse()?;
+            if city_offset_map.last().map(|x| x.0) != Some(code_4) {
+                city_offset_map.push((code_4, data.len()));
+            }
+            let mut name_char_index_list = Vec::new();
+            for c in stem.chars() {
+                match char_map.get(&c) {
+                    Some(i) => name_char_index_list.push(*i),
+                    None => {
+                        return Err(RegionError::Message(format!(
+                            "char {c} is not in the char table"
+                        )))
+                    }
+                }
+            }
+            let code_int: u32 = code.parse()?;
+            let record = if wide {
+                let established_year = self
+                    .established_years
+                    .get(*code)
+                    .copied()
+                    .unwrap_or_default();
+                encode_record_v2(
+                    code_int,
+                    region_type,
+                    &name_char_index_list,
+                    *discard_year,
+                    established_year,
+                )
+            } else {
+                encode_record(code_int, region_type, &name_char_index_list, *discard_year)
+            };
+            data.extend(record.map_err(|x| RegionError::Message(format!("{code}: {x}")))?);
+        }
+        // 继承关系区，地区码4个字节，数量1个字节，然后是继承地区的地区码
+        let mut successor_bytes = Vec::new();
+        for (code, successors) in &self.successors {
+            if successors.is_empty() || successors.len() > u8::MAX as usize {
+                return Err(RegionError::Message(format!(
+                    "{code}: the number of successors must be between 1 and 255"
+                )));
+            }
+            let (code_int, discard_year) = successor_code(records, code)?;
+            if discard_year == 0 {
+                return Err(RegionError::Message(format!(
+                    "{code}: successors can only be recorded for discarded codes"
+                )));
+            }
+            successor_bytes.extend(code_int.to_be_bytes());
+            successor_bytes.push(successors.len() as u8);
+            for successor in successors {
+                successor_bytes.extend(successor_code(records, successor)?.0.to_be_bytes());
+            }
+        }
+        let offset_codes: Vec<i32> = offset_map.iter().map(|x| x.0).collect();
+        if !wide && offset_codes != PROVINCE_CODES {
+            return Err(RegionError::Message(
+                "records must contain all provinces".to_string(),
+            ));
+        }
+        // 字符集，v2是UTF-8编码，之前是GBK编码
+        let chars = String::from_iter(char_list);
+        let char_bytes = if wide {
+            chars.into_bytes()
+        } else {
+            GBK.encode(&chars, EncoderTrap::Strict)
+                .map_err(|x| RegionError::Message(x.to_string()))?
+        };
+        let mut res = match self.format {
+            DataFormat::Legacy => {
+                // 版本号4个字节，最高位是字符集排序的标志，索引区偏移2个字节
+                // 和带文件头的格式区分
+                if self.version.to_be_bytes() == *MAGIC {
+                    return Err(RegionError::Message(format!(
+                        "invalid version: {}",
+                        self.version
+                    )));
+                }
+                let mut version = self.version;
+                if self.char_order == CharOrder::Gbk {
+                    version |= SORTED_CHARS_FLAG;
+                }
+                let mut header = version.to_be_bytes().to_vec();
+                let index_offset = 6 + data.len();
+                if index_offset > u16::MAX as usize {
+                    return Err(RegionError::Message("too many records".to_string()));
+                }
+                header.extend((index_offset as u16).to_be_bytes());
+                header
+            }
+            DataFormat::Indexed | DataFormat::V2 => {
+                // RGCN、格式版本、标志、版本号、区的数量，然后是省份索引、地级索引和字符集的偏移
+                let (format_version, province_entry, city_entry) =
+                    if wide { (2, 5, 6) } else { (1, 3, 4) };
+                let mut flags = FLAG_CHECKSUM;
+                if self.char_order == CharOrder::Gbk {
+                    flags |= FLAG_SORTED_CHARS;
+                }
+                let mut header = MAGIC.to_vec();
+                header.extend([format_version, flags]);
+                header.extend(self.version.to_be_bytes());
+                // 有继承关系时增加第4个区
+                let section_count = if successor_bytes.is_empty() {
+                    SECTION_COUNT
+                } else {
+                    SECTION_COUNT_WITH_SUCCESSORS
+                };
+                header.push(section_count as u8);
+                // 区的偏移之后是4个字节的校验和
+                let header_len = header.len() + section_count * 4 + 4;
+                let province_index = header_len + data.len();
+                let city_index = province_index + offset_map.len() * province_entry;
+                let char_offset = city_index + city_offset_map.len() * city_entry;
+                let successors_offset = char_offset + char_bytes.len();
+                if successors_offset + successor_bytes.len() > u32::MAX as usize {
+                    return Err(RegionError::Message("too many records".to_string()));
+                }
+                let mut offsets = vec![province_index, city_index, char_offset];
+                if !successor_bytes.is_empty() {
+                    offsets.push(successors_offset);
+                }
+                for offset in offsets {
+                    header.extend((offset as u32).to_be_bytes());
+                }
+                header.extend([0; 4]);
+                header
+            }
+        };
+        let header_len = res.len();
+        if !wide && header_len + data.len() > MAX_INDEX_OFFSET as usize {
+            return Err(RegionError::Message("too many records".to_string()));
+        }
+        res.extend(data);
+        if wide {
+            // 省份代码1个字节，偏移4个字节
+            for (offset_code, offset) in offset_map {
+                res.push(offset_code as u8);
+                res.extend(((header_len + offset) as u32).to_be_bytes());
+            }
+            // 4位地区码2个字节，偏移4个字节
+            for (offset_code, offset) in city_offset_map {
+                res.extend((offset_code as u16).to_be_bytes());
+                res.extend(((header_len + offset) as u32).to_be_bytes());
+            }
+        } else {
+            // 索引区，省份代码高7位，偏移低17位，刚好3个字节
+            for (offset_code, offset) in offset_map {
+                let combine = ((offset_code as u32) << 17) + (header_len + offset) as u32;
+                res.extend(&combine.to_be_bytes()[1..]);
+            }
+            // 地级索引，4位地区码高15位，偏移低17位，4个字节，最初的格式没有
+            if self.format == DataFormat::Indexed {
+                for (offset_code, offset) in city_offset_map {
+                    let combine = ((offset_code as u32) << 17) + (header_len + offset) as u32;
+                    res.extend(combine.to_be_bytes());
+                }
+            }
+        }
+        res.extend(char_bytes);
+        res.extend(successor_bytes);
+        if self.format != DataFormat::Legacy {
+            let checksum = crc32(&res[header_len..]);
+            res[header_len - 4..header_len].copy_from_slice(&checksum.to_be_bytes());
+        }
+        Ok(res)
+    }
+
+    /// 打包并写入`writer`
+    pub fn write_to<W: Write>(
+        &self,
+        records: &[(&str, &str, u32)],
+        mut writer: W,
+    ) -> Result<(), RegionError> {
+        let data = self.pack(records)?;
+        writer.write_all(&data)?;
+        Ok(())
+    }
+}
+
+/// 继承关系中的地区码需要是记录中的6位地区码
+fn successor_code(records: &[(&str, &str, u32)], code: &str) -> Result<(u32, u32), RegionError> {
+    match records.binary_search_by(|x| x.0.cmp(code)) {
+        Ok(i) if code.len() == 6 => Ok((code.parse()?, records[i].2)),
+        _ => Err(RegionError::Message(format!(
+            "successor code {code} is not in the records"
+        ))),
+    }
+}
+
+/// v2之前的记录：1个字节的大小，3个字节的地区码和类型，然后是名称和废止年份
+fn encode_record(
+    code: u32,
+    region_type: RegionType,
+    name_char_index_list: &[u32],
+    discard_year: u32,
+) -> Result<Vec<u8>, String> {
+    // 废止年份，值为 discard_year - 1980， 占6bit
+    let discard_year_int = match discard_year {
+        0 => 0,
+        1981..=2043 => discard_year - 1980,
+        _ => return Err(format!("discard year {discard_year} is out of range")),
+    };
+    let u8_list = encode_u8_list(name_char_index_list, discard_year_int);
+    let total_bytes = u8_list.len() + 4;
+    if total_bytes > u8::MAX as usize {
+        return Err("name is too long".to_string());
+    }
+    // 记录字节大小
+    let mut res = vec![total_bytes as u8];
+    // 地区码和类型，高20位是地区码，低4位是类型
+    let code_type = (code << 4) + region_type.id() as u32;
+    res.extend(&code_type.to_be_bytes()[1..]);
+    res.extend(u8_list);
+    Ok(res)
+}
+
+/// v2的记录：2个字节的大小，4个字节的地区码，1个字节的类型，1个字节的标志，
+/// 然后是废止年份和设立年份，有的时候各2个字节，最后每个字符索引2个字节
+fn encode_record_v2(
+    code: u32,
+    region_type: RegionType,
+    name_char_index_list: &[u32],
+    discard_year: u32,
+    established_year: u32,
+) -> Result<Vec<u8>, String> {
+    let mut flags = 0;
+    let mut body = Vec::new();
+    for (year, flag, label) in [
+        (discard_year, RECORD_FLAG_DISCARD_YEAR, "discard"),
+        (
+            established_year,
+            RECORD_FLAG_ESTABLISHED_YEAR,
+            "established",
+        ),
+    ] {
+        if year > 0 {
+            let year =
+                u16::try_from(year).map_err(|_| format!("{label} year {year} is out of range"))?;
+            flags |= flag;
+            body.extend(year.to_be_bytes());
+        }
+    }
+    body.insert(0, flags);
+    for i in name_char_index_list {
+        body.extend((*i as u16).to_be_bytes());
+    }
+    let total_bytes = body.len() + 7;
+    if total_bytes > u16::MAX as usize {
+        return Err("name is too long".to_string());
+    }
+    let mut res = (total_bytes as u16).to_be_bytes().to_vec();
+    res.extend(code.to_be_bytes());
+    res.push(region_type.id() as u8);
+    res.extend(body);
+    Ok(res)
+}
+
+/// 将12位的字符索引依次拼接，不足1个字节的补0，有废止年份时再追加1个字节
+fn encode_u8_list(name_char_index_list: &[u32], discard_year_int: u32) -> Vec<u8> {
+    let mut res = Vec::new();
+    let mut last_int = 0;
+    let mut last_bits = 0;
+    for c in name_char_index_list {
+        last_int = (last_int << 12) + c;
+        last_bits += 12;
+        while last_bits >= 8 {
+            last_bits -= 8;
+            res.push((last_int >> last_bits) as u8);
+        }
+        last_int &= (1 << last_bits) - 1;
+    }
+    if last_bits > 0 {
+        res.push((last_int << (8 - last_bits)) as u8);
+    }
+    if discard_year_int > 0 {
+        res.push(discard_year_int as u8);
+    }
+    res
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::fs;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{region::Region, verify::VerifyIssue, MatchMode};
+
+    fn load_records(file: &str) -> Vec<(String, String, u32)> {
+        let json_data: Value = serde_json::from_str(&fs::read_to_string(file).unwrap()).unwrap();
+        json_data
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| {
+                let discard_year = x.get(2).and_then(|x| x.as_str()).unwrap_or("");
+                (
+                    x[0].as_str().unwrap().to_string(),
+                    x[1].as_str().unwrap().to_string(),
+                    discard_year.parse().unwrap_or(0),
+                )
+            })
+            .collect()
+    }
+
+    /// 转换成`pack`使用的借用形式
+    pub(crate) fn as_refs(records: &[(String, String, u32)]) -> Vec<(&str, &str, u32)> {
+        records
+            .iter()
+            .map(|(code, name, year)| (code.as_str(), name.as_str(), *year))
+            .collect()
+    }
+
+    #[test]
+    fn test_writer() {
+        for (txt, dat) in [
+            ("data/region.txt", "data/region.dat"),
+            ("data/region_full.txt", "data/region_full.dat"),
+        ] {
+            let records = load_records(txt);
+            let records = as_refs(&records);
+            let region = Region::open(dat).unwrap();
+            let data = RegionWriter::new(2024092911)
+                .with_char_table(region.char_table())
+                .pack(&records)
+                .unwrap();
+            assert_eq!(data, fs::read(dat).unwrap());
+            // 默认的字符顺序
+            let data = RegionWriter::new(2024092911).pack(&records).unwrap();
+            let path = std::env::temp_dir().join("region_cn_writer.dat");
+            fs::write(&path, data).unwrap();
+            let packed = Region::open(&path).unwrap();
+            assert_eq!(
+                packed.search_with_data("530925").unwrap().name,
+                "云南省临沧市双江拉祜族佤族布朗族傣族自治县"
+            );
+            let _ = fs::remove_file(path);
+        }
+        assert!(RegionWriter::new(2024092911)
+            .pack(&[("110000", "北京市", 0)])
+            .is_err());
+    }
+
+    #[test]
+    fn test_writer_char_order() {
+        let records = load_records("data/region.txt");
+        let mut records = as_refs(&records);
+        let writer = RegionWriter::new(2024092911).with_char_order(CharOrder::Gbk);
+        let data = writer.pack(&records).unwrap();
+        // 名称的顺序不影响字符集
+        let (a, b) = (records[1], records[2]);
+        records[1] = (a.0, b.1, a.2);
+        records[2] = (b.0, a.1, b.2);
+        let swapped = writer.pack(&records).unwrap();
+        let path = std::env::temp_dir().join("region_cn_writer_gbk.dat");
+        fs::write(&path, &data).unwrap();
+        let region = Region::open(&path).unwrap();
+        assert_eq!(region.char_order(), CharOrder::Gbk);
+        assert_eq!(region.get_version().unwrap(), "2024092911");
+        let swapped_path = std::env::temp_dir().join("region_cn_writer_gbk_swapped.dat");
+        fs::write(&swapped_path, &swapped).unwrap();
+        assert_eq!(
+            region.char_table(),
+            Region::open(&swapped_path).unwrap().char_table()
+        );
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(swapped_path);
+        assert_eq!(
+            Region::open("data/region.dat").unwrap().char_order(),
+            CharOrder::Unspecified
+        );
+    }
+
+    #[test]
+    fn test_writer_indexed() {
+        let records = load_records("data/region_full.txt");
+        let records = as_refs(&records);
+        let data = RegionWriter::new(2024092911)
+            .with_char_order(CharOrder::Gbk)
+            .with_format(DataFormat::Indexed)
+            .pack(&records)
+            .unwrap();
+        assert_eq!(&data[..4], b"RGCN");
+        let region = Region::from_bytes(data).unwrap();
+        assert_eq!(region.data_format(), DataFormat::Indexed);
+        assert_eq!(region.char_order(), CharOrder::Gbk);
+        assert_eq!(region.get_version().unwrap(), "2024092911");
+        let legacy = Region::open("data/region_full.dat").unwrap();
+        assert_eq!(legacy.data_format(), DataFormat::Legacy);
+        for (code, _, _) in &records {
+            let result = region.search_with_data(code).unwrap();
+            let expected = legacy.search_with_data(code).unwrap();
+            assert_eq!(result.levels, expected.levels);
+            assert_eq!(result.discard_year, expected.discard_year);
+        }
+        for code in ["530999", "539999", "110000", "429004"] {
+            assert_eq!(
+                region.search_with_data(code).unwrap().levels,
+                legacy.search_with_data(code).unwrap().levels
+            );
+        }
+        assert_eq!(region.get_record_from_data().unwrap().len(), records.len());
+        let report = region.verify().unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.checksum_matched, Some(true));
+        // 修改记录中的一个字节，校验和不再匹配
+        let mut data = RegionWriter::new(2024092911)
+            .with_format(DataFormat::Indexed)
+            .pack(&records)
+            .unwrap();
+        let header_len = 11 + 3 * 4 + 4;
+        data[header_len + 5] ^= 1;
+        let report = Region::from_bytes(data).unwrap().verify().unwrap();
+        assert_eq!(report.checksum_matched, Some(false));
+        assert!(matches!(
+            report.issues[..],
+            [VerifyIssue::ChecksumMismatch { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_writer_v2() {
+        let records = load_records("data/region_full.txt");
+        let records = as_refs(&records);
+        let data = RegionWriter::new(2024092911)
+            .with_format(DataFormat::V2)
+            .pack(&records)
+            .unwrap();
+        let region = Region::from_bytes(data).unwrap();
+        assert_eq!(region.data_format(), DataFormat::V2);
+        let legacy = Region::open("data/region_full.dat").unwrap();
+        for (code, _, _) in &records {
+            let result = region.search_with_data(code).unwrap();
+            let expected = legacy.search_with_data(code).unwrap();
+            assert_eq!(result.levels, expected.levels);
+            assert_eq!(result.discard_year, expected.discard_year);
+        }
+        assert_eq!(
+            region.search_with_trie("110103").unwrap().name,
+            "北京市崇文区"
+        );
+        // 不在原来34个省份中的代码、1980年之前的废止年份和GBK之外的字符
+        let records = [
+            ("110000", "北京市", 0),
+            ("110101", "东城区", 0),
+            ("900000", "测试省", 0),
+            ("900100", "𠮷市", 1970),
+        ];
+        let data = RegionWriter::new(2024092911)
+            .with_format(DataFormat::V2)
+            .pack(&records)
+            .unwrap();
+        let region = Region::from_bytes(data).unwrap();
+        let result = region.search_with_data("900100").unwrap();
+        assert_eq!(result.name, "测试省𠮷市");
+        assert_eq!(result.discard_year, 1970);
+        assert!(matches!(
+            region.search_with_data("120000"),
+            Err(RegionError::UnknownProvince(12))
+        ));
+        for format in [DataFormat::Legacy, DataFormat::Indexed] {
+            assert!(RegionWriter::new(2024092911)
+                .with_format(format)
+                .pack(&records)
+                .is_err());
+        }
+        // v2也只支持6位地区码
+        assert!(RegionWriter::new(2024092911)
+            .with_format(DataFormat::V2)
+            .pack(&[("110000", "北京市", 0), ("110101001", "东华门街道", 0)])
+            .is_err());
+        // 设立年份
+        let writer = RegionWriter::new(2024092911)
+            .with_established_years(HashMap::from([("900100".to_string(), 1950)]));
+        assert!(writer.clone().pack(&records).is_err());
+        let data = writer.with_format(DataFormat::V2).pack(&records).unwrap();
+        let region = Region::from_bytes(data).unwrap();
+        let result = region.search_with_data("900100").unwrap();
+        assert_eq!((result.established_year, result.discard_year), (1950, 1970));
+        assert_eq!(
+            region.search_with_trie("900100").unwrap().established_year,
+            1950
+        );
+        assert!(region.search_as_of("900100", 1960).is_ok());
+        assert!(matches!(
+            region.search_as_of("900100", 1949),
+            Err(RegionError::NotValidInYear {
+                established_year: 1950,
+                ..
+            })
+        ));
+        assert!(region.search_as_of("900100", 1970).is_err());
+    }
+
+    #[test]
+    fn test_writer_successors() {
+        let records = load_records("data/region_full.txt");
+        let records = as_refs(&records);
+        let successors = HashMap::from([
+            ("110103".to_string(), vec!["110101".to_string()]),
+            ("110104".to_string(), vec!["110102".to_string()]),
+        ]);
+        for format in [DataFormat::Indexed, DataFormat::V2] {
+            let data = RegionWriter::new(2024092911)
+                .with_format(format)
+                .with_successors(successors.clone())
+                .pack(&records)
+                .unwrap();
+            let region = Region::from_bytes(data).unwrap();
+            let result = region.successors("110103").unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].name, "北京市东城区");
+            assert_eq!(region.successors("110102").unwrap().len(), 0);
+            assert!(region.verify().unwrap().is_ok());
+        }
+        assert!(RegionWriter::new(2024092911)
+            .with_successors(successors)
+            .pack(&records)
+            .is_err());
+        assert!(RegionWriter::new(2024092911)
+            .with_format(DataFormat::Indexed)
+            .with_successors(HashMap::from([(
+                "110103".to_string(),
+                vec!["110199".to_string()]
+            )]))
+            .pack(&records)
+            .is_err());
+        // 未废止的地区码不能有继承地区
+        let result = RegionWriter::new(2024092911)
+            .with_format(DataFormat::Indexed)
+            .with_successors(HashMap::from([(
+                "110101".to_string(),
+                vec!["110102".to_string()],
+            )]))
+            .pack(&records);
+        assert!(matches!(result, Err(RegionError::Message(x)) if x.contains("110101")));
+    }
+
+    /// 单个省份的记录超过4000个字节时也能找到最后的记录
+    #[test]
+    fn test_writer_large_province() {
+        let mut records = load_records("data/region.txt");
+        let extra = (1..100)
+            .flat_map(|i| [format!("1398{i:02}"), format!("1399{i:02}")])
+            .map(|code| (code, "测试测试测试测试测试县".to_string(), 0));
+        records.extend(extra);
+        records.sort();
+        let records = as_refs(&records);
+        let data = RegionWriter::new(2024092911).pack(&records).unwrap();
+        let region = Region::from_bytes(data).unwrap();
+        let result = region
+            .search_with_data_mode("139999", MatchMode::Strict)
+            .unwrap();
+        assert_eq!(result.name, "河北省测试测试测试测试测试县");
+        assert_eq!(region.search_with_data("140000").unwrap().name, "山西省");
+    }
+}